@@ -0,0 +1,77 @@
+use std::error::Error;
+use std::fmt;
+
+use super::{ SeatId, Ticket };
+
+/// Where an item pushed with `try_one_in` ended up.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Admission<T> {
+    /// The item was placed in the store, in the seat identified by <b>seat</b>.
+    Admitted { seat: SeatId, ticket: Ticket },
+    /// The store was at capacity and the item joined the queue. <b>position</b> is the
+    /// number of items ahead of it in arrival order; which item is admitted next is up to
+    /// the QueueDiscipline. <b>dropped</b> holds a waiting item the Overflow policy removed
+    /// to make room for it.
+    Queued { position: usize, dropped: Option<T>, ticket: Ticket }
+}
+
+impl<T> Admission<T> {
+    /// Ticket issued for the item, which can be used to follow it or take it back out.
+    pub fn ticket(&self) -> Ticket {
+        match *self {
+            Admission::Admitted { ticket, .. } => ticket,
+            Admission::Queued { ticket, .. } => ticket
+        }
+    }
+}
+
+/// Dictates what happens when an item arrives at a queue that is already at its
+/// maximum length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overflow {
+    /// The arriving item is turned away.
+    RejectNewcomer,
+    /// The item that has waited longest is removed and the arriving item joins the back.
+    DropOldest,
+    /// A randomly chosen waiting item is removed and the arriving item joins the back.
+    DropRandom
+}
+
+/// Why an item pushed with `try_one_in` was turned away. The item is handed back so that
+/// the caller can decide what to do with it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rejected<T> {
+    /// The current Phase only allows essential items into the store.
+    NonEssential(T),
+    /// The store is at capacity and the queue is at its maximum length.
+    QueueFull(T),
+    /// The current Phase is Closed.
+    Closed(T),
+    /// A CapacityPolicy other than a Phase does not allow the item in.
+    Denied(T)
+}
+
+impl<T> Rejected<T> {
+    /// Returns the item that was turned away.
+    pub fn into_inner(self) -> T {
+        match self {
+            Rejected::NonEssential(item) |
+            Rejected::QueueFull(item) |
+            Rejected::Closed(item) |
+            Rejected::Denied(item) => item
+        }
+    }
+}
+
+impl<T> fmt::Display for Rejected<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejected::NonEssential(_) => write!(f, "only essential items may enter during the current phase"),
+            Rejected::QueueFull(_) => write!(f, "the store is at capacity and the queue is full"),
+            Rejected::Closed(_) => write!(f, "no items may enter while closed"),
+            Rejected::Denied(_) => write!(f, "the capacity policy does not allow the item in")
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for Rejected<T> {}
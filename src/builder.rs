@@ -0,0 +1,278 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rand::{ Rng, SeedableRng };
+use rand::rngs::StdRng;
+
+use super::{ CapacityPolicy, Clock, ContactLog, Entry, OIOOObserver, Eviction, ExitStrategy, Fifo, FloorPlan, OIOO, Occupant, Overflow, Phase, Placement,
+             QueueDiscipline, Rounding, Seats, SystemClock, UniformRandom, Venue, SOCIAL_DISTANCE };
+
+/// Configures and creates an OIOO. Anything not set on the builder keeps the same
+/// default used by `OIOO::new`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// # extern crate rand;
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+///
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 10 })
+///     .social_distance(2)
+///     .rng(StdRng::seed_from_u64(42))
+///     .build();
+/// oioo.one_in(10);
+/// ```
+pub struct OIOOBuilder<T, R = StdRng> {
+    policy: Box<dyn CapacityPolicy<T> + Send>,
+    rounding: Rounding,
+    social_distance: usize,
+    floor_plan: Option<FloorPlan>,
+    /// Whether the capacity is the number of items the FloorPlan seats under the Placement.
+    seats_limit_capacity: bool,
+    placement: Placement,
+    capacity: Option<usize>,
+    eviction: Eviction,
+    max_queue_len: Option<usize>,
+    overflow: Overflow,
+    discipline: Box<dyn QueueDiscipline<T> + Send>,
+    exit_strategy: Box<dyn ExitStrategy<T> + Send>,
+    clock: Box<dyn Clock + Send>,
+    max_stay: Option<Duration>,
+    contact_range: Option<usize>,
+    observers: Vec<Box<dyn OIOOObserver<T> + Send>>,
+    rng: R
+}
+
+impl<T> OIOOBuilder<T> {
+    /// Starts configuring an OIOO that begins in the passed in Phase.
+    pub fn new(phase: Phase) -> OIOOBuilder<T> {
+        OIOOBuilder::with_policy(phase)
+    }
+
+    /// Starts configuring an OIOO laid out on the passed in FloorPlan, whose capacity is
+    /// the number of items the room seats under the configured Placement rather than a
+    /// Phase percentage of occupancy.
+    pub fn from_floor_plan(floor_plan: FloorPlan) -> OIOOBuilder<T> {
+        let mut builder = OIOOBuilder::with_policy(floor_plan.clone()).floor_plan(floor_plan);
+        builder.seats_limit_capacity = true;
+        builder
+    }
+
+    /// Starts configuring an OIOO whose capacity is limited by the passed in policy
+    /// instead of a Phase.
+    pub fn with_policy<P: CapacityPolicy<T> + Send + 'static>(policy: P) -> OIOOBuilder<T> {
+        OIOOBuilder {
+            policy: Box::new(policy),
+            rounding: Rounding::Floor,
+            social_distance: SOCIAL_DISTANCE,
+            floor_plan: None,
+            seats_limit_capacity: false,
+            placement: Placement::FirstFit,
+            capacity: None,
+            eviction: Eviction::AwaitExits,
+            max_queue_len: None,
+            overflow: Overflow::RejectNewcomer,
+            discipline: Box::new(Fifo),
+            exit_strategy: Box::new(UniformRandom),
+            clock: Box::new(SystemClock::new()),
+            max_stay: None,
+            contact_range: None,
+            observers: Vec::<Box<dyn OIOOObserver<T> + Send>>::new(),
+            rng: StdRng::from_entropy()
+        }
+    }
+}
+
+impl<T, R: Rng> OIOOBuilder<T, R> {
+    /// Number of empty spaces kept between items in the store. Defaults to 6.
+    pub fn social_distance(mut self, social_distance: usize) -> Self {
+        self.social_distance = social_distance;
+        self
+    }
+
+    /// Lays the store out on a two-dimensional room instead of a single line. Items are
+    /// placed in a cell that keeps the FloorPlan's distance from everyone inside, chosen
+    /// according to the Placement; if there is none, they wait in the queue. The social distance
+    /// set with `social_distance` does not apply to a FloorPlan.
+    pub fn floor_plan(mut self, floor_plan: FloorPlan) -> Self {
+        self.floor_plan = Some(floor_plan);
+        self
+    }
+
+    /// Placement used to pick which free seat an admitted item takes. Defaults to
+    /// Placement::FirstFit.
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Rounding applied when a Phase allows a fractional capacity, both now and on any later
+    /// call to `set_phase`. Defaults to Rounding::Floor.
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Overrides the capacity computed from the Phase. The override only lasts until
+    /// the next call to `set_phase` or `set_policy`.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Policy applied to surplus items when `set_phase` reduces capacity.
+    pub fn eviction(mut self, eviction: Eviction) -> Self {
+        self.eviction = eviction;
+        self
+    }
+
+    /// Limits the queue to <b>max_queue_len</b> items, applying the Overflow policy to any
+    /// item that arrives while it is full. The queue is unbounded by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// use oioo::Overflow;
+    ///
+    /// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 2 })
+    ///     .queue_limit(1, Overflow::DropOldest)
+    ///     .build();
+    /// oioo.one_in(10); // contained in store
+    /// oioo.one_in(20); // contained in queue
+    /// assert_eq!(oioo.one_in(30), Some(20)); // 20 is dropped from the queue to make room
+    /// ```
+    pub fn queue_limit(mut self, max_queue_len: usize, overflow: Overflow) -> Self {
+        self.max_queue_len = Some(max_queue_len);
+        self.overflow = overflow;
+        self
+    }
+
+    /// Discipline used to decide which waiting item is admitted when space becomes
+    /// available. Defaults to Fifo.
+    pub fn discipline<D: QueueDiscipline<T> + Send + 'static>(mut self, discipline: D) -> Self {
+        self.discipline = Box::new(discipline);
+        self
+    }
+
+    /// Strategy used to decide which item leaves the store on `one_out`. Defaults to
+    /// UniformRandom.
+    pub fn exit_strategy<E: ExitStrategy<T> + Send + 'static>(mut self, exit_strategy: E) -> Self {
+        self.exit_strategy = Box::new(exit_strategy);
+        self
+    }
+
+    /// Clock used to timestamp items as they are admitted into the store. Defaults to
+    /// SystemClock.
+    pub fn clock<C: Clock + Send + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Longest an item may stay in the store before `expire` removes it. Items may stay
+    /// indefinitely by default.
+    pub fn max_stay(mut self, max_stay: Duration) -> Self {
+        self.max_stay = Some(max_stay);
+        self
+    }
+
+    /// Keeps a ContactLog of items that sat within <b>range</b> positions of each other.
+    /// No log is kept by default.
+    pub fn contact_log(mut self, range: usize) -> Self {
+        self.contact_range = Some(range);
+        self
+    }
+
+    /// Adds an observer to be notified as items move through the OIOO. Observers are
+    /// notified in the order they were added.
+    pub fn observer<O: OIOOObserver<T> + Send + 'static>(mut self, observer: O) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Random number generator used to decide which item leaves the store.
+    pub fn rng<S: Rng>(self, rng: S) -> OIOOBuilder<T, S> {
+        OIOOBuilder {
+            policy: self.policy,
+            rounding: self.rounding,
+            social_distance: self.social_distance,
+            floor_plan: self.floor_plan,
+            seats_limit_capacity: self.seats_limit_capacity,
+            placement: self.placement,
+            capacity: self.capacity,
+            eviction: self.eviction,
+            max_queue_len: self.max_queue_len,
+            overflow: self.overflow,
+            discipline: self.discipline,
+            exit_strategy: self.exit_strategy,
+            clock: self.clock,
+            max_stay: self.max_stay,
+            contact_range: self.contact_range,
+            observers: self.observers,
+            rng
+        }
+    }
+
+    /// Creates the configured OIOO.
+    pub fn build(mut self) -> OIOO<T, R> {
+        if self.seats_limit_capacity {
+            if let Some(ref floor_plan) = self.floor_plan {
+                self.policy = Box::new(floor_plan.placed_with(self.placement));
+            }
+        }
+
+        let venue = Venue {
+            social_distance: self.social_distance,
+            rounding: self.rounding,
+            inside: 0,
+            waiting: 0,
+            held: 0
+        };
+        let capacity = self.capacity.unwrap_or_else(|| self.policy.allowed_capacity(&venue));
+
+        OIOO {
+            store: Vec::<Occupant<T>>::with_capacity(capacity),
+            seats: Seats::new(),
+            placement: self.placement,
+            queue: VecDeque::<Entry<T>>::new(),
+            held: VecDeque::<Entry<T>>::new(),
+            isolated: VecDeque::<Entry<T>>::new(),
+            capacity_override: self.capacity,
+            social_distance: self.social_distance,
+            floor_plan: self.floor_plan,
+            policy: self.policy,
+            rounding: self.rounding,
+            admissions: 0,
+            tickets: 0,
+            eviction: self.eviction,
+            max_queue_len: self.max_queue_len,
+            overflow: self.overflow,
+            discipline: self.discipline,
+            exit_strategy: self.exit_strategy,
+            clock: self.clock,
+            max_stay: self.max_stay,
+            contact_log: self.contact_range.map(ContactLog::new),
+            observers: self.observers,
+            rng: self.rng
+        }
+    }
+
+    /// Creates the configured OIOO and pushes every item from the iterator into it as if
+    /// by `one_in`, filling the store first and the queue after.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// let oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 4 })
+    ///     .build_from(0..5);
+    /// assert_eq!(oioo.into_iter().count(), 5);
+    /// ```
+    pub fn build_from<I: IntoIterator<Item = T>>(self, iter: I) -> OIOO<T, R> {
+        let mut oioo = self.build();
+        oioo.extend(iter);
+        oioo
+    }
+}
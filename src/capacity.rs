@@ -0,0 +1,77 @@
+use super::{ Essentiality, Phase, Rounding };
+
+/// Decides how many items an OIOO allows in its store and which items may enter at all.
+/// Phase is the built-in policy; implement this trait to encode any other rules.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use oioo::{ CapacityPolicy, Essentiality, Venue };
+///
+/// /// Allows one item per 100 square feet, and only even numbers.
+/// struct Ordinance { square_feet: usize }
+///
+/// impl CapacityPolicy<usize> for Ordinance {
+///     fn allowed_capacity(&self, _venue: &Venue) -> usize {
+///         self.square_feet / 100
+///     }
+///
+///     fn may_enter(&self, item: &usize, _essentiality: Option<Essentiality>, _venue: &Venue) -> bool {
+///         item % 2 == 0
+///     }
+/// }
+///
+/// let mut oioo = oioo::OIOOBuilder::with_policy(Ordinance { square_feet: 250 }).build();
+/// oioo.extend(0..6);
+/// assert_eq!(oioo.store_len(), 2);
+/// assert_eq!(oioo.queue_len(), 1);
+/// assert_eq!(oioo.held_len(), 3);
+/// ```
+pub trait CapacityPolicy<T> {
+    /// Number of items allowed in the store. This is re-evaluated whenever an item arrives
+    /// or leaves, so it may depend on the current state of the venue.
+    fn allowed_capacity(&self, venue: &Venue) -> usize;
+
+    /// Whether the passed in item may enter the store at all. Items that may not are held
+    /// outside by `one_in` and rejected by `try_one_in`. <b>essentiality</b> is None for
+    /// items pushed without one. Every item may enter by default.
+    fn may_enter(&self, _item: &T, _essentiality: Option<Essentiality>, _venue: &Venue) -> bool {
+        true
+    }
+
+    /// Returns the Phase this policy represents, if it is one.
+    fn phase(&self) -> Option<Phase> {
+        None
+    }
+}
+
+/// Description and current state of the venue an OIOO models, passed to a CapacityPolicy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Venue {
+    /// Number of empty spaces kept between items in the store.
+    pub social_distance: usize,
+    /// Rounding the OIOO was configured with.
+    pub rounding: Rounding,
+    /// Number of items inside the store.
+    pub inside: usize,
+    /// Number of items waiting in the queue.
+    pub waiting: usize,
+    /// Number of items held outside.
+    pub held: usize
+}
+
+impl<T> CapacityPolicy<T> for Phase {
+    fn allowed_capacity(&self, venue: &Venue) -> usize {
+        self.capacity(venue.rounding)
+    }
+
+    fn may_enter(&self, _item: &T, essentiality: Option<Essentiality>, _venue: &Venue) -> bool {
+        self.admits(essentiality)
+    }
+
+    fn phase(&self) -> Option<Phase> {
+        Some(*self)
+    }
+}
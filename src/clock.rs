@@ -0,0 +1,81 @@
+use std::sync::{ Arc, Mutex };
+use std::time::{ Duration, Instant };
+
+/// Source of the current time, used to timestamp items as they are admitted into the store.
+/// Times are measured as the duration since an arbitrary, fixed starting point.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// Measures real time elapsed since the clock was created. This is the default clock.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemClock {
+    start: Instant
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to, for tests and simulations. Clones share the same
+/// time, so a clone can be kept to advance the clock after handing it to an OIOO.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use std::time::Duration;
+/// use oioo::ManualClock;
+///
+/// let clock = ManualClock::new();
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 2 })
+///     .clock(clock.clone())
+///     .max_stay(Duration::from_secs(60))
+///     .build();
+/// oioo.one_in(10); // contained in store
+/// oioo.one_in(20); // contained in queue
+///
+/// clock.advance(Duration::from_secs(60));
+/// assert_eq!(oioo.expire(), vec![10]); // 20 is moved into the store
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    now: Arc<Mutex<Duration>>
+}
+
+impl ManualClock {
+    /// Creates a clock starting at zero.
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    /// Moves the clock forward by the passed in duration.
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    /// Sets the clock to the passed in time.
+    pub fn set(&self, now: Duration) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
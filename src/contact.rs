@@ -0,0 +1,192 @@
+use std::cmp::Ordering;
+use std::collections::{ BTreeMap, HashMap };
+use std::time::Duration;
+
+use super::{ Exposure, Metric, Position, RiskModel, Ticket };
+
+/// A period two items spent inside the store within range of each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    /// The item that was admitted first.
+    pub a: Ticket,
+    /// The item that was admitted second.
+    pub b: Ticket,
+    /// When the later of the two items was admitted.
+    pub start: Duration,
+    /// When the first of the two items left, or None if both are still inside.
+    pub end: Option<Duration>,
+    /// Distance between the two items, in positions.
+    pub distance: f64
+}
+
+impl Contact {
+    /// Returns the other item in the contact, or None if the passed in ticket is neither.
+    pub fn other(&self, ticket: Ticket) -> Option<Ticket> {
+        if self.a == ticket {
+            Some(self.b)
+        } else if self.b == ticket {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+
+    /// How long the contact lasted, counting a contact that hasn't ended up to <b>now</b>.
+    pub fn duration(&self, now: Duration) -> Duration {
+        self.end.unwrap_or(now).saturating_sub(self.start)
+    }
+}
+
+/// Record of which items were inside the store within <b>range</b> positions of each other,
+/// and when. Items are identified by the Ticket issued when they were pushed into the OIOO.
+/// Positions are compared using the FloorPlan's metric, or along the line when there is no
+/// FloorPlan; the empty slots kept for social distance count as positions.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use std::time::Duration;
+/// use oioo::ManualClock;
+///
+/// let clock = ManualClock::new();
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 6 })
+///     .social_distance(1)
+///     .contact_log(2)
+///     .clock(clock.clone())
+///     .build();
+///
+/// let a = oioo.try_one_in(10).unwrap().ticket(); // position 0
+/// let b = oioo.try_one_in(20).unwrap().ticket(); // position 2
+/// let c = oioo.try_one_in(30).unwrap().ticket(); // position 4
+/// clock.advance(Duration::from_secs(60));
+/// oioo.leave(b);
+///
+/// let contacts = oioo.contact_log().unwrap().contacts_of(b, Duration::from_secs(0));
+/// let mut others = contacts.iter().filter_map(|c| c.other(b)).collect::<Vec<_>>();
+/// others.sort();
+/// assert_eq!(others, vec![a, c]);
+/// assert!(contacts.iter().all(|c| c.end == Some(Duration::from_secs(60))));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ContactLog {
+    range: usize,
+    contacts: Vec<Contact>,
+    /// Indexes in "contacts" of the contacts each item inside the store may still be part of.
+    open: HashMap<Ticket, Vec<usize>>,
+    /// Risk modifiers set for items, which otherwise default to 1.
+    modifiers: HashMap<Ticket, f64>
+}
+
+impl ContactLog {
+    pub(crate) fn new(range: usize) -> ContactLog {
+        ContactLog {
+            range,
+            contacts: Vec::<Contact>::new(),
+            open: HashMap::new(),
+            modifiers: HashMap::new()
+        }
+    }
+
+    /// Greatest number of positions apart two items can be and still be in contact.
+    pub fn range(&self) -> usize {
+        self.range
+    }
+
+    /// Iterates over every contact recorded, in the order they started.
+    pub fn contacts(&self) -> impl Iterator<Item = &Contact> + '_ {
+        self.contacts.iter()
+    }
+
+    /// Returns the contacts the item was part of that hadn't ended before <b>since</b>, in
+    /// the order they started.
+    pub fn contacts_of(&self, ticket: Ticket, since: Duration) -> Vec<Contact> {
+        self.contacts.iter()
+                     .filter(|c| c.other(ticket).is_some() && c.end.map_or(true, |end| end >= since))
+                     .copied()
+                     .collect()
+    }
+
+    /// Exports the contact graph: every pair of items that was ever in contact along with
+    /// the total time they spent in contact, counting contacts that haven't ended up to
+    /// <b>now</b>. Pairs are ordered by ticket, lowest first.
+    pub fn graph(&self, now: Duration) -> Vec<(Ticket, Ticket, Duration)> {
+        let mut edges = BTreeMap::<(Ticket, Ticket), Duration>::new();
+        for contact in &self.contacts {
+            let pair = (contact.a.min(contact.b), contact.a.max(contact.b));
+            *edges.entry(pair).or_default() += contact.duration(now);
+        }
+
+        edges.into_iter().map(|((a, b), duration)| (a, b, duration)).collect()
+    }
+
+    /// Risk modifier of the item, 1 unless one was set with `OIOO::set_risk_modifier`.
+    pub fn modifier(&self, ticket: Ticket) -> f64 {
+        self.modifiers.get(&ticket).copied().unwrap_or(1.0)
+    }
+
+    /// Scores every item that was in contact with the flagged item using the passed in
+    /// RiskModel, counting contacts that haven't ended up to <b>now</b>. Items are ranked
+    /// most exposed first; ties keep the order the items were first in contact.
+    pub fn rank_exposed(&self, flagged: Ticket, model: &dyn RiskModel, now: Duration) -> Vec<(Ticket, f64)> {
+        let mut ranked = Vec::<(Ticket, f64)>::new();
+        for contact in &self.contacts {
+            let exposed = match contact.other(flagged) {
+                Some(exposed) => exposed,
+                None => continue
+            };
+
+            let score = model.score(&Exposure {
+                distance: contact.distance,
+                duration: contact.duration(now),
+                source_modifier: self.modifier(flagged),
+                exposed_modifier: self.modifier(exposed)
+            });
+            match ranked.iter_mut().find(|(t, _)| *t == exposed) {
+                Some((_, total)) => *total += score,
+                None => ranked.push((exposed, score))
+            }
+        }
+
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        ranked
+    }
+
+    pub(crate) fn set_modifier(&mut self, ticket: Ticket, modifier: f64) {
+        self.modifiers.insert(ticket, modifier);
+    }
+
+    /// Starts a contact between the item entering at <b>position</b> and every item inside
+    /// within range of it.
+    pub(crate) fn enter<I>(&mut self, ticket: Ticket, position: Position, metric: Metric, now: Duration, inside: I)
+        where I: Iterator<Item = (Ticket, Position)> {
+        let mut opened = Vec::<usize>::new();
+        for (other, other_position) in inside {
+            if metric.reaches(position, other_position, self.range) {
+                let index = self.contacts.len();
+                let distance = metric.distance(position, other_position);
+                self.contacts.push(Contact { a: other, b: ticket, start: now, end: None, distance });
+                self.open.entry(other).or_default().push(index);
+                opened.push(index);
+            }
+        }
+
+        self.open.insert(ticket, opened);
+    }
+
+    /// Ends every contact the item leaving is still part of.
+    pub(crate) fn leave(&mut self, ticket: Ticket, now: Duration) {
+        for index in self.open.remove(&ticket).unwrap_or_default() {
+            let contact = &mut self.contacts[index];
+            if contact.end.is_none() {
+                contact.end = Some(now);
+            }
+        }
+    }
+
+    /// Ends every contact still open, as everyone has left.
+    pub(crate) fn leave_all(&mut self, now: Duration) {
+        let inside = self.open.keys().copied().collect::<Vec<_>>();
+        inside.into_iter().for_each(|ticket| self.leave(ticket, now));
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::VecDeque;
+
+use rand::{ Rng, RngCore };
+
+use super::Entry;
+
+/// Decides which waiting item is admitted into the store when space becomes available.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// # extern crate rand;
+/// use oioo::{ Line, QueueDiscipline };
+/// use rand::RngCore;
+///
+/// /// Admits the smallest waiting number first.
+/// struct Smallest;
+///
+/// impl QueueDiscipline<usize> for Smallest {
+///     fn select(&mut self, line: &Line<'_, usize>, _rng: &mut dyn RngCore) -> usize {
+///         (0..line.len()).min_by_key(|&i| line.get(i)).unwrap()
+///     }
+/// }
+///
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 2 })
+///     .discipline(Smallest)
+///     .build();
+/// oioo.extend(vec![30, 20, 10]);
+/// assert_eq!(oioo.one_out(), Some(30));
+/// assert_eq!(oioo.one_out(), Some(10));
+/// ```
+pub trait QueueDiscipline<T> {
+    /// Returns the index, counted from the front of the line, of the item to admit next.
+    /// Only called when the line has at least one item in it.
+    ///
+    /// # Panics
+    ///
+    /// The OIOO panics if the returned index is out of range.
+    fn select(&mut self, line: &Line<'_, T>, rng: &mut dyn RngCore) -> usize;
+}
+
+/// A read-only view of the items waiting in the queue, front of the line first.
+pub struct Line<'a, T> {
+    queue: &'a VecDeque<Entry<T>>
+}
+
+impl<'a, T> Line<'a, T> {
+    pub(crate) fn new(queue: &'a VecDeque<Entry<T>>) -> Line<'a, T> {
+        Line { queue }
+    }
+
+    /// Number of items waiting.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if no items are waiting.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the item at the passed in index, counted from the front of the line.
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        self.queue.get(index).map(|e| &e.item)
+    }
+
+    /// Iterates over the waiting items, front of the line first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator + 'a {
+        self.queue.iter().map(|e| &e.item)
+    }
+}
+
+/// Admits the item that has waited longest. This is the default discipline.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fifo;
+
+impl<T> QueueDiscipline<T> for Fifo {
+    fn select(&mut self, _line: &Line<'_, T>, _rng: &mut dyn RngCore) -> usize {
+        0
+    }
+}
+
+/// Admits the item that arrived most recently.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Lifo;
+
+impl<T> QueueDiscipline<T> for Lifo {
+    fn select(&mut self, line: &Line<'_, T>, _rng: &mut dyn RngCore) -> usize {
+        line.len() - 1
+    }
+}
+
+/// Admits a uniformly random waiting item, as in a ticket lottery.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Lottery;
+
+impl<T> QueueDiscipline<T> for Lottery {
+    fn select(&mut self, line: &Line<'_, T>, rng: &mut dyn RngCore) -> usize {
+        rng.gen_range(0, line.len())
+    }
+}
+
+/// Admits the waiting item with the highest priority class, as returned by the passed in
+/// function. Items in the same class are admitted in the order they arrived.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use oioo::PriorityClass;
+///
+/// struct Person { age: usize }
+///
+/// // seniors are admitted before everyone else
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 2 })
+///     .discipline(PriorityClass::new(|p: &Person| p.age >= 65))
+///     .build();
+/// oioo.one_in(Person { age: 30 }); // contained in store
+/// oioo.one_in(Person { age: 40 });
+/// oioo.one_in(Person { age: 70 });
+/// oioo.one_out();
+///
+/// assert_eq!(oioo.inside().next().unwrap().age, 70);
+/// ```
+pub struct PriorityClass<F> {
+    class: F
+}
+
+impl<F> PriorityClass<F> {
+    pub fn new(class: F) -> PriorityClass<F> {
+        PriorityClass { class }
+    }
+}
+
+impl<T, P: Ord, F: Fn(&T) -> P> QueueDiscipline<T> for PriorityClass<F> {
+    fn select(&mut self, line: &Line<'_, T>, _rng: &mut dyn RngCore) -> usize {
+        // reversed so that ties resolve to the item closest to the front
+        line.iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, item)| (self.class)(item))
+            .map_or(0, |(index, _)| index)
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use rand::{ Rng, RngCore };
+
+use super::{ Occupant, Position };
+
+/// Decides which item leaves the store on `one_out`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// # extern crate rand;
+/// use oioo::{ ExitStrategy, Room };
+/// use rand::RngCore;
+///
+/// /// The largest number always leaves first.
+/// struct Largest;
+///
+/// impl ExitStrategy<usize> for Largest {
+///     fn select(&mut self, room: &Room<'_, usize>, _rng: &mut dyn RngCore) -> usize {
+///         (0..room.len()).max_by_key(|&i| room.get(i)).unwrap()
+///     }
+/// }
+///
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 10 })
+///     .exit_strategy(Largest)
+///     .build();
+/// oioo.extend(vec![10, 30, 20]);
+/// assert_eq!(oioo.one_out(), Some(30));
+/// ```
+pub trait ExitStrategy<T> {
+    /// Returns the index of the item in the room that leaves next. Only called when the
+    /// room has at least one item in it.
+    ///
+    /// # Panics
+    ///
+    /// The OIOO panics if the returned index is out of range.
+    fn select(&mut self, room: &Room<'_, T>, rng: &mut dyn RngCore) -> usize;
+}
+
+/// A read-only view of the items inside the store. Indexes are only meaningful until the
+/// next item leaves.
+pub struct Room<'a, T> {
+    store: &'a [Occupant<T>]
+}
+
+impl<'a, T> Room<'a, T> {
+    pub(crate) fn new(store: &'a [Occupant<T>]) -> Room<'a, T> {
+        Room { store }
+    }
+
+    /// Number of items inside.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Returns true if no items are inside.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Returns the item at the passed in index.
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        self.store.get(index).map(|o| &o.entry.item)
+    }
+
+    /// Returns the order in which the item at the passed in index was admitted; items
+    /// admitted earlier have smaller values.
+    pub fn admitted(&self, index: usize) -> Option<u64> {
+        self.store.get(index).map(|o| o.admitted)
+    }
+
+    /// Returns the time, according to the OIOO's Clock, at which the item at the passed in
+    /// index was admitted.
+    pub fn admitted_at(&self, index: usize) -> Option<Duration> {
+        self.store.get(index).map(|o| o.since)
+    }
+
+    /// Returns where in the store the item at the passed in index is.
+    pub fn position(&self, index: usize) -> Option<Position> {
+        self.store.get(index).map(|o| o.position)
+    }
+
+    /// Iterates over the items inside, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        self.store.iter().map(|o| &o.entry.item)
+    }
+}
+
+/// Every item inside is equally likely to leave. This is the default strategy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UniformRandom;
+
+impl<T> ExitStrategy<T> for UniformRandom {
+    fn select(&mut self, room: &Room<'_, T>, rng: &mut dyn RngCore) -> usize {
+        rng.gen_range(0, room.len())
+    }
+}
+
+/// The item admitted earliest leaves first.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstIn;
+
+impl<T> ExitStrategy<T> for FirstIn {
+    fn select(&mut self, room: &Room<'_, T>, _rng: &mut dyn RngCore) -> usize {
+        (0..room.len()).min_by_key(|&i| room.admitted(i)).unwrap_or(0)
+    }
+}
+
+/// The item admitted most recently leaves first.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LastIn;
+
+impl<T> ExitStrategy<T> for LastIn {
+    fn select(&mut self, room: &Room<'_, T>, _rng: &mut dyn RngCore) -> usize {
+        (0..room.len()).max_by_key(|&i| room.admitted(i)).unwrap_or(0)
+    }
+}
+
+/// The item that has stayed inside the longest leaves first. Since stays are measured
+/// from admission, this is the same as FirstIn.
+pub type LongestStay = FirstIn;
+
+/// Items leave at random, with a chance proportional to the weight returned for each by
+/// the passed in function. Negative and NaN weights count as zero. If any weight is
+/// infinite, one of the infinitely weighted items leaves, each equally likely; if every
+/// weight is zero, each item is equally likely to leave.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use oioo::WeightedRandom;
+///
+/// // an item weighted 0 never leaves while others are inside
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 10 })
+///     .exit_strategy(WeightedRandom::new(|x: &usize| if *x == 10 { 0.0 } else { 1.0 }))
+///     .build();
+/// oioo.extend(vec![10, 20, 30]);
+/// assert_ne!(oioo.one_out(), Some(10));
+/// assert_ne!(oioo.one_out(), Some(10));
+/// assert_eq!(oioo.one_out(), Some(10));
+/// ```
+pub struct WeightedRandom<F> {
+    weight: F
+}
+
+impl<F> WeightedRandom<F> {
+    pub fn new(weight: F) -> WeightedRandom<F> {
+        WeightedRandom { weight }
+    }
+}
+
+impl<T, F: Fn(&T) -> f64> ExitStrategy<T> for WeightedRandom<F> {
+    fn select(&mut self, room: &Room<'_, T>, rng: &mut dyn RngCore) -> usize {
+        let mut weights = room.iter()
+                              .map(|item| (self.weight)(item).max(0.0))
+                              .collect::<Vec<_>>();
+        let infinite = (0..weights.len()).filter(|&i| weights[i].is_infinite()).collect::<Vec<_>>();
+        if !infinite.is_empty() {
+            return infinite[rng.gen_range(0, infinite.len())];
+        }
+
+        let mut total = weights.iter().sum::<f64>();
+        if total.is_infinite() {
+            // finite weights can still overflow when summed; scaling keeps their proportions
+            let largest = weights.iter().cloned().fold(0.0, f64::max);
+            weights.iter_mut().for_each(|w| *w /= largest);
+            total = weights.iter().sum::<f64>();
+        }
+        if total <= 0.0 {
+            return rng.gen_range(0, room.len());
+        }
+
+        let mut target = rng.gen::<f64>() * total;
+        for (index, weight) in weights.iter().enumerate() {
+            if target < *weight {
+                return index;
+            }
+            target -= weight;
+        }
+
+        // rounding can leave a sliver of the total unclaimed; give it to the last weighted item
+        weights.iter().rposition(|w| *w > 0.0).unwrap_or(0)
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::BTreeSet;
+
+use super::{ CapacityPolicy, Packing, Placement, Venue };
+use super::packing::PACKING_SEARCH_LIMIT;
+
+/// Location of an item in the store. Without a FloorPlan the store is a single row and an
+/// item's column is its slot, including the empty slots kept for social distance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    pub row: usize,
+    pub column: usize
+}
+
+impl Position {
+    pub fn new(row: usize, column: usize) -> Position {
+        Position { row, column }
+    }
+}
+
+/// How the distance between two positions is measured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    /// Straight-line distance.
+    Euclidean,
+    /// Distance walking along rows and columns only.
+    Manhattan
+}
+
+impl Metric {
+    /// Distance between two positions, in cells.
+    pub fn distance(&self, a: Position, b: Position) -> f64 {
+        let rows = a.row.abs_diff(b.row) as f64;
+        let columns = a.column.abs_diff(b.column) as f64;
+        match *self {
+            Metric::Euclidean => (rows * rows + columns * columns).sqrt(),
+            Metric::Manhattan => rows + columns
+        }
+    }
+
+    /// Whether two positions are strictly closer than <b>radius</b>, compared exactly
+    /// rather than through floating point.
+    pub(crate) fn within(&self, a: Position, b: Position, radius: usize) -> bool {
+        let rows = a.row.abs_diff(b.row);
+        let columns = a.column.abs_diff(b.column);
+        match *self {
+            Metric::Euclidean => rows * rows + columns * columns < radius * radius,
+            Metric::Manhattan => rows + columns < radius
+        }
+    }
+
+    /// Whether two positions are no further apart than <b>range</b>, compared exactly.
+    pub(crate) fn reaches(&self, a: Position, b: Position, range: usize) -> bool {
+        let rows = a.row.abs_diff(b.row);
+        let columns = a.column.abs_diff(b.column);
+        match *self {
+            Metric::Euclidean => rows * rows + columns * columns <= range * range,
+            Metric::Manhattan => rows + columns <= range
+        }
+    }
+}
+
+/// A two-dimensional room the store can be laid out on instead of a single line. Every
+/// occupied cell is kept at least <b>radius</b> cells away from every other occupied cell.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use oioo::{ FloorPlan, Metric, Position };
+///
+/// // a 3x3 room where occupied cells can't touch, even diagonally
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Reopened { occupancy: 9 })
+///     .floor_plan(FloorPlan::grid(3, 3, 2, Metric::Euclidean))
+///     .build();
+/// oioo.extend(0..5);
+///
+/// assert_eq!(oioo.store_len(), 4); // one in each corner
+/// assert_eq!(oioo.queue_len(), 1);
+/// assert!(oioo.seated().any(|(position, _)| position == Position::new(2, 2)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloorPlan {
+    rows: usize,
+    columns: usize,
+    radius: usize,
+    metric: Metric,
+    /// Whether each cell, in row-major order, can be occupied at all.
+    usable: Vec<bool>,
+    /// Whether each cell, in row-major order, is currently occupied.
+    occupied: Vec<bool>,
+    /// Indexes of the cells an item could currently be placed in without breaking distance.
+    available: BTreeSet<usize>,
+    /// Number of items first-fit placement, or the Placement set with `placed_with`, seats
+    /// in the empty room.
+    capacity: usize
+}
+
+impl FloorPlan {
+    /// Creates a rectangular room where every cell can be occupied.
+    pub fn grid(rows: usize, columns: usize, radius: usize, metric: Metric) -> FloorPlan {
+        FloorPlan::with_cells(rows, columns, radius, metric, vec![true; rows * columns])
+    }
+
+    pub(crate) fn with_cells(rows: usize, columns: usize, radius: usize, metric: Metric, usable: Vec<bool>) -> FloorPlan {
+        let mut floor_plan = FloorPlan {
+            rows,
+            columns,
+            radius,
+            metric,
+            occupied: vec![false; usable.len()],
+            available: (0..usable.len()).filter(|&i| usable[i]).collect(),
+            usable,
+            capacity: 0
+        };
+
+        // a single row-major pass seats the same cells as repeatedly taking the first free one
+        for position in floor_plan.positions() {
+            if floor_plan.is_available(position) {
+                floor_plan.take(position);
+                floor_plan.capacity += 1;
+            }
+        }
+        floor_plan.vacate();
+
+        floor_plan
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Number of items the empty room seats when each is placed in the first available
+    /// cell, as under Placement::FirstFit. An OIOO built with `OIOOBuilder::from_floor_plan`
+    /// instead uses the number its own Placement seats as its capacity.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Copy of this room whose capacity is the number of items the passed in Placement
+    /// seats in the empty room.
+    pub(crate) fn placed_with(&self, placement: Placement) -> FloorPlan {
+        let mut floor_plan = self.clone();
+        floor_plan.vacate();
+        if placement == Placement::BestFit {
+            floor_plan.capacity = 0;
+            while let Some(position) = floor_plan.find_best_seat() {
+                floor_plan.take(position);
+                floor_plan.capacity += 1;
+            }
+            floor_plan.vacate();
+        }
+
+        floor_plan
+    }
+
+    /// Finds the largest set of cells that can be occupied at once. The search is only
+    /// proven to finish for rooms of a few dozen cells, e.g. an 8 by 8 grid; beyond that it
+    /// usually stops at its work limit and returns the best packing found, which is never
+    /// smaller than `capacity`. Use `Packing::is_exact` to tell the two apart.
+    pub fn pack(&self) -> Packing {
+        self.pack_with_limit(PACKING_SEARCH_LIMIT)
+    }
+
+    /// Same as `pack` but does at most <b>limit</b> work looking for a larger packing, counted
+    /// in candidate cells looked at.
+    pub fn pack_with_limit(&self, limit: usize) -> Packing {
+        Packing::solve(self, limit)
+    }
+
+    /// Creates an empty copy of this room where only the cells in the passed in packing
+    /// can be occupied, so an OIOO built from it admits into exactly those seats.
+    pub fn seated_at(&self, packing: &Packing) -> FloorPlan {
+        let mut usable = vec![false; self.usable.len()];
+        for position in packing.positions() {
+            if let Some(i) = self.index(*position) {
+                usable[i] = self.usable[i];
+            }
+        }
+
+        FloorPlan::with_cells(self.rows, self.columns, self.radius, self.metric, usable)
+    }
+
+    /// Minimum distance, in cells, kept between occupied cells.
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    /// Whether the cell at the passed in position can be occupied at all.
+    pub fn is_usable(&self, position: Position) -> bool {
+        self.index(position).is_some_and(|i| self.usable[i])
+    }
+
+    /// Whether the cell at the passed in position is currently occupied.
+    pub fn is_occupied(&self, position: Position) -> bool {
+        self.index(position).is_some_and(|i| self.occupied[i])
+    }
+
+    /// Whether an item could be placed at the passed in position without breaking distance.
+    pub fn is_available(&self, position: Position) -> bool {
+        self.is_usable(position) && !self.is_occupied(position) && !self.has_neighbor(position)
+    }
+
+    /// Returns the first available cell in row-major order.
+    pub(crate) fn find_seat(&self) -> Option<Position> {
+        self.available.iter().next().map(|&i| self.position(i))
+    }
+
+    /// Returns the available cell that leaves the most other cells available, preferring
+    /// the first in row-major order.
+    pub(crate) fn find_best_seat(&self) -> Option<Position> {
+        self.available.iter()
+            .map(|&i| self.position(i))
+            .min_by_key(|p| self.nearby(*p).filter(|other| other != p && self.available.contains(&(other.row * self.columns + other.column))).count())
+    }
+
+    pub(crate) fn take(&mut self, position: Position) {
+        if let Some(i) = self.index(position) {
+            self.occupied[i] = true;
+            for other in self.nearby(position).collect::<Vec<_>>() {
+                self.available.remove(&(other.row * self.columns + other.column));
+            }
+        }
+    }
+
+    pub(crate) fn release(&mut self, position: Position) {
+        if let Some(i) = self.index(position) {
+            self.occupied[i] = false;
+            // only the released cell and those near it can have become available
+            for other in self.nearby(position).filter(|p| self.is_available(*p)).collect::<Vec<_>>() {
+                self.available.insert(other.row * self.columns + other.column);
+            }
+        }
+    }
+
+    /// Marks every cell as unoccupied.
+    pub(crate) fn vacate(&mut self) {
+        self.occupied.iter_mut().for_each(|o| *o = false);
+        let usable = &self.usable;
+        self.available = (0..usable.len()).filter(|&i| usable[i]).collect();
+    }
+
+    /// Iterates over every cell in row-major order.
+    pub(crate) fn positions(&self) -> impl Iterator<Item = Position> {
+        let columns = self.columns;
+        (0..self.rows * columns).map(move |i| Position::new(i / columns, i % columns))
+    }
+
+    /// Whether any occupied cell is closer to the passed in position than the radius.
+    fn has_neighbor(&self, position: Position) -> bool {
+        self.nearby(position).any(|other| self.is_occupied(other))
+    }
+
+    /// Iterates over the cells closer to the passed in position than the radius, including
+    /// the position itself.
+    fn nearby(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        let reach = self.radius.saturating_sub(1);
+        let rows = position.row.saturating_sub(reach)..(position.row + reach + 1).min(self.rows);
+        let columns = position.column.saturating_sub(reach)..(position.column + reach + 1).min(self.columns);
+
+        rows.flat_map(move |row| columns.clone().map(move |column| Position::new(row, column)))
+            .filter(move |other| self.metric.within(position, *other, self.radius))
+    }
+
+    fn position(&self, index: usize) -> Position {
+        Position::new(index / self.columns, index % self.columns)
+    }
+
+    pub(crate) fn index(&self, position: Position) -> Option<usize> {
+        if position.row < self.rows && position.column < self.columns {
+            Some(position.row * self.columns + position.column)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> CapacityPolicy<T> for FloorPlan {
+    fn allowed_capacity(&self, _venue: &Venue) -> usize {
+        self.capacity
+    }
+}
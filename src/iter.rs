@@ -0,0 +1,69 @@
+use rand::Rng;
+
+use super::OIOO;
+
+/// A consuming iterator over the items of an OIOO, created by `into_iter`. Store items
+/// are returned in exit order, followed by waiting items from the front of the queue,
+/// then held items and finally isolated items, the same order as `OIOO::drain`.
+pub struct IntoIter<T, R> {
+    oioo: OIOO<T, R>
+}
+
+impl<T, R: Rng> IntoIter<T, R> {
+    pub(crate) fn new(oioo: OIOO<T, R>) -> IntoIter<T, R> {
+        IntoIter { oioo }
+    }
+}
+
+impl<T, R: Rng> Iterator for IntoIter<T, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.oioo.take_next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.oioo.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, R: Rng> ExactSizeIterator for IntoIter<T, R> {}
+
+/// A draining iterator over the items of an OIOO, created by `drain`. Store items are
+/// returned in exit order, followed by waiting items from the front of the queue, then
+/// held items and finally isolated items. Any items not consumed are removed when the
+/// iterator is dropped.
+pub struct Drain<'a, T, R: Rng> {
+    oioo: &'a mut OIOO<T, R>
+}
+
+impl<'a, T, R: Rng> Drain<'a, T, R> {
+    pub(crate) fn new(oioo: &'a mut OIOO<T, R>) -> Drain<'a, T, R> {
+        Drain { oioo }
+    }
+}
+
+impl<'a, T, R: Rng> Iterator for Drain<'a, T, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.oioo.take_next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.oioo.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, R: Rng> ExactSizeIterator for Drain<'a, T, R> {}
+
+impl<'a, T, R: Rng> Drop for Drain<'a, T, R> {
+    fn drop(&mut self) {
+        self.oioo.clear_store();
+        self.oioo.queue.clear();
+        self.oioo.held.clear();
+        self.oioo.isolated.clear();
+    }
+}
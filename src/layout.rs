@@ -0,0 +1,182 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{ FloorPlan, Metric, Position };
+
+/// What a single cell of a Layout holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    /// <b>#</b> - can't be occupied.
+    Wall,
+    /// <b>.</b> - open floor, which can be occupied unless the layout has fixed seats.
+    Floor,
+    /// <b>D</b> - a door, which is always kept clear.
+    Door,
+    /// <b>S</b> - a fixed seat. If a layout has any, only seats can be occupied.
+    Seat
+}
+
+impl Cell {
+    fn from_char(c: char) -> Option<Cell> {
+        match c {
+            '#' => Some(Cell::Wall),
+            '.' => Some(Cell::Floor),
+            'D' => Some(Cell::Door),
+            'S' => Some(Cell::Seat),
+            _ => None
+        }
+    }
+}
+
+/// A venue described as a text grid, one character per cell: <b>#</b> for walls, <b>.</b>
+/// for floor, <b>D</b> for doors and <b>S</b> for fixed seats. Every row must be the same
+/// width; blank lines before and after the grid are ignored.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use oioo::{ Layout, Metric };
+///
+/// let layout: Layout = [
+///     "#######",
+///     "#S.S.S#",
+///     "#.....#",
+///     "#S.S.S#",
+///     "###D###",
+/// ].join("\n").parse().unwrap();
+///
+/// let floor_plan = layout.floor_plan(2, Metric::Euclidean);
+/// assert_eq!(floor_plan.capacity(), 6);
+///
+/// let mut oioo = oioo::OIOOBuilder::from_floor_plan(floor_plan).build();
+/// oioo.extend(0..8);
+/// assert_eq!(oioo.store_len(), 6);
+/// assert_eq!(oioo.queue_len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    rows: usize,
+    columns: usize,
+    cells: Vec<Cell>
+}
+
+impl Layout {
+    /// Parses a layout from text. Errors report the 1-based line and column of the problem,
+    /// counting lines from the start of the text.
+    pub fn parse(text: &str) -> Result<Layout, LayoutError> {
+        let lines = text.lines()
+                        .enumerate()
+                        .map(|(i, line)| (i + 1, line.trim_end_matches('\r')))
+                        .skip_while(|(_, line)| line.trim().is_empty())
+                        .collect::<Vec<_>>();
+        let last = lines.iter()
+                        .rposition(|(_, line)| !line.trim().is_empty())
+                        .ok_or(LayoutError::Empty)?;
+
+        let columns = lines[0].1.chars().count();
+        let mut cells = Vec::<Cell>::with_capacity(columns * (last + 1));
+        for &(line_number, line) in &lines[..=last] {
+            let width = line.chars().count();
+            if width != columns {
+                return Err(LayoutError::RaggedRow { line: line_number, column: width.min(columns) + 1, width, expected: columns });
+            }
+
+            for (i, c) in line.chars().enumerate() {
+                let cell = Cell::from_char(c).ok_or(LayoutError::UnknownCell { line: line_number, column: i + 1, found: c })?;
+                cells.push(cell);
+            }
+        }
+
+        Ok(Layout { rows: last + 1, columns, cells })
+    }
+
+    /// Reads and parses a layout file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Layout, LayoutError> {
+        Layout::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the cell at the passed in position, or None if it is outside the layout.
+    pub fn cell(&self, position: Position) -> Option<Cell> {
+        if position.row < self.rows && position.column < self.columns {
+            Some(self.cells[position.row * self.columns + position.column])
+        } else {
+            None
+        }
+    }
+
+    /// Creates a FloorPlan from this layout where occupied cells are kept at least
+    /// <b>radius</b> cells apart. Only seats can be occupied if the layout has any;
+    /// otherwise any floor cell can be.
+    pub fn floor_plan(&self, radius: usize, metric: Metric) -> FloorPlan {
+        let usable_cell = if self.cells.contains(&Cell::Seat) { Cell::Seat } else { Cell::Floor };
+        let usable = self.cells.iter().map(|c| *c == usable_cell).collect();
+
+        FloorPlan::with_cells(self.rows, self.columns, radius, metric, usable)
+    }
+}
+
+impl FromStr for Layout {
+    type Err = LayoutError;
+
+    fn from_str(text: &str) -> Result<Layout, LayoutError> {
+        Layout::parse(text)
+    }
+}
+
+/// Why a Layout could not be created.
+#[derive(Debug)]
+pub enum LayoutError {
+    /// The layout file could not be read.
+    Io(io::Error),
+    /// A character other than <b>#</b>, <b>.</b>, <b>D</b> or <b>S</b> was found.
+    UnknownCell { line: usize, column: usize, found: char },
+    /// A row is a different width than the first row. <b>column</b> is where the row first
+    /// differs: one past its last cell if it is too short, or its first extra cell if it is
+    /// too long.
+    RaggedRow { line: usize, column: usize, width: usize, expected: usize },
+    /// The layout has no rows.
+    Empty
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Io(e) => write!(f, "could not read layout: {}", e),
+            LayoutError::UnknownCell { line, column, found } => {
+                write!(f, "line {}, column {}: unknown cell '{}'", line, column, found)
+            },
+            LayoutError::RaggedRow { line, column, width, expected } => {
+                write!(f, "line {}, column {}: row is {} cells wide, expected {}", line, column, width, expected)
+            },
+            LayoutError::Empty => write!(f, "layout has no rows")
+        }
+    }
+}
+
+impl Error for LayoutError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LayoutError::Io(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for LayoutError {
+    fn from(e: io::Error) -> LayoutError {
+        LayoutError::Io(e)
+    }
+}
@@ -1,29 +1,143 @@
-use rand::{ Rng };
-
-/// Dictates the current Phase, which limits the capabilities of an OIOO instance.
-pub enum Phase {
-    One { 
-        occupancy: usize, 
-        is_essential: bool 
-    },
-    Two { occupancy: usize },
+#![allow(clippy::needless_arbitrary_self_type, clippy::len_zero)]
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rand::{ Rng, SeedableRng };
+use rand::rngs::StdRng;
+
+mod admission;
+mod builder;
+mod capacity;
+mod clock;
+mod contact;
+mod discipline;
+mod exit;
+mod floor_plan;
+mod iter;
+mod layout;
+mod observer;
+mod packing;
+mod phase;
+mod risk;
+mod seat;
+mod simulation;
+mod ticket;
+
+pub use admission::{ Admission, Overflow, Rejected };
+pub use builder::OIOOBuilder;
+pub use capacity::{ CapacityPolicy, Venue };
+pub use clock::{ Clock, ManualClock, SystemClock };
+pub use contact::{ Contact, ContactLog };
+pub use discipline::{ Fifo, Lifo, Line, Lottery, PriorityClass, QueueDiscipline };
+pub use exit::{ ExitStrategy, FirstIn, LastIn, LongestStay, Room, UniformRandom, WeightedRandom };
+pub use floor_plan::{ FloorPlan, Metric, Position };
+pub use iter::{ Drain, IntoIter };
+pub use layout::{ Cell, Layout, LayoutError };
+pub use observer::OIOOObserver;
+pub use packing::Packing;
+pub use phase::{ Essentiality, Phase, Rounding };
+pub use risk::{ Exposure, InverseSquare, RiskModel };
+pub use seat::{ Placement, SeatId };
+pub use simulation::{ Health, Outcome, Simulation };
+pub use ticket::{ Status, Ticket };
+
+use seat::Seats;
+
+/// Dictates what happens to items already in the store when a change of Phase
+/// leaves more items in the store than the new capacity allows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Eviction {
+    /// Surplus items stay in the store; no one is admitted from the queue until
+    /// enough items have left for the store to drop below the new capacity.
+    AwaitExits,
+    /// The most recently admitted surplus items are moved to the front of the queue,
+    /// keeping the order in which they were admitted.
+    ToQueueFront
 }
 
-/// Number of empty spaces between items.
+/// Default number of empty spaces between items.
 static SOCIAL_DISTANCE: usize = 6;
 
+/// An item along with the essentiality it was pushed with and the ticket issued for it.
+/// Items pushed with `one_in` have no essentiality of their own and follow the current Phase.
+struct Entry<T> {
+    item: T,
+    essentiality: Option<Essentiality>,
+    ticket: Ticket
+}
+
+/// An entry that has been admitted into the store, along with the order and time it was
+/// admitted at and the seat it took.
+struct Occupant<T> {
+    entry: Entry<T>,
+    admitted: u64,
+    since: Duration,
+    seat: SeatId,
+    position: Position
+}
+
 /// A data structure intended as an alternative to FIFO or LIFO: One-in, One-out. Items are 
 /// pushed into the data structure and are retrieved randomly. Each item is padded with
-/// a number of empty slots based on recommended social-distance guidelines. The capacity
+/// a number of empty slots based on recommended social-distance guidelines. The padding is
+/// kept virtually: an item in seat <b>k</b> of the store occupies slot <b>k * (social distance + 1)</b>.
+/// Items keep their seat until they leave; the holes left behind are filled by later
+/// admissions according to the OIOO's Placement. The capacity
 /// of the OIOO is set upon creation; any excess items are contained in a queue which is 
 /// automatically used to fill the main store when space becomes available. 
-pub struct OIOO<T> {
+///
+/// The random number generator used to pick exiting items defaults to an entropy seeded
+/// `StdRng`, which keeps the OIOO `Send`, but any `Rng` can be supplied, allowing a seeded
+/// generator to reproduce the same exit sequence.
+pub struct OIOO<T, R = StdRng> {
     /// Used as primary storage of items pushed into the OIOO up until the capacity is hit.
-    store: Vec::<Option<T>>,
+    /// Items are kept contiguous; where each one sits is tracked by "seats".
+    store: Vec::<Occupant<T>>,
+    /// Seat each item in "store" sits in, which doesn't change as others leave.
+    seats: Seats,
+    /// Decides which free seat an item admitted into "store" takes.
+    placement: Placement,
     /// Used as overflow of items that can't fit in in store due to capacity limitations.
-    queue: Vec::<T>,
-    /// Total number of items contained in "store" determined by Phase used to initialize the OIOO.
-    capacity: usize
+    /// Items are admitted from the front in the order they arrived.
+    queue: VecDeque::<Entry<T>>,
+    /// Items the current Phase does not allow into the store, waiting for a Phase that does.
+    held: VecDeque::<Entry<T>>,
+    /// Items removed from "store" or the lines by `quarantine`, waiting to be released.
+    isolated: VecDeque::<Entry<T>>,
+    /// Capacity set on the builder, used instead of the policy's until the policy changes.
+    capacity_override: Option<usize>,
+    /// Number of empty spaces between items in "store".
+    social_distance: usize,
+    /// Room "store" is laid out on, if not a single line.
+    floor_plan: Option<FloorPlan>,
+    /// Policy limiting the capacity of the OIOO, usually a Phase.
+    policy: Box<dyn CapacityPolicy<T> + Send>,
+    /// Rounding applied when the Phase allows a fractional capacity.
+    rounding: Rounding,
+    /// Number of items admitted into "store" so far, used to order occupants by admission.
+    admissions: u64,
+    /// Number of items pushed into the OIOO so far, used to issue each one a Ticket.
+    tickets: u64,
+    /// Policy applied to surplus items when a change of Phase reduces capacity.
+    eviction: Eviction,
+    /// Maximum number of items allowed to join "queue", if any.
+    max_queue_len: Option<usize>,
+    /// Policy applied to arriving items when "queue" is at its maximum length.
+    overflow: Overflow,
+    /// Decides which item in "queue" is admitted when space becomes available.
+    discipline: Box<dyn QueueDiscipline<T> + Send>,
+    /// Decides which item in "store" leaves on one_out.
+    exit_strategy: Box<dyn ExitStrategy<T> + Send>,
+    /// Source of the time items are admitted into "store" at.
+    clock: Box<dyn Clock + Send>,
+    /// Longest an item may stay in "store" before `expire` removes it, if any.
+    max_stay: Option<Duration>,
+    /// Record of which items in "store" sat near each other, if kept.
+    contact_log: Option<ContactLog>,
+    /// Notified as items are admitted, queued, promoted and leave "store".
+    observers: Vec::<Box<dyn OIOOObserver<T> + Send>>,
+    /// Source of randomness used to select which item leaves the store.
+    rng: R
 }
 
 impl<T> OIOO<T> {
@@ -32,28 +146,170 @@ impl<T> OIOO<T> {
     /// <b>Using Phase One</b>
     /// <ul>
     ///     <li>capacity is set to 25% of the passed in Phase::One's occupancy value</li>
-    ///     <li>if <b>is_essential</b> is false, the OIOO will be unable to contain items in the main store</li>
+    ///     <li>only essential items are allowed into the store; non-essential items are held outside
+    ///         in a separate line until the OIOO moves into a Phase that allows them</li>
+    ///     <li>if <b>is_essential</b> is false, items pushed with <b>one_in</b> are treated as non-essential</li>
     /// </ul>
     ///
     /// <b>Using Phase Two</b>
     /// <ul>
     ///     <li>capacity is set to 50% of the passed in Phase::Two's occupancy value</li>
     /// </ul>
+    ///
+    /// <b>Using Phase Three</b>
+    /// <ul>
+    ///     <li>capacity is set to 75% of the passed in Phase::Three's occupancy value</li>
+    /// </ul>
+    ///
+    /// <b>Using Phase Reopened</b>
+    /// <ul>
+    ///     <li>capacity is set to 100% of the passed in Phase::Reopened's occupancy value</li>
+    /// </ul>
+    ///
+    /// <b>Using Phase Closed</b>
+    /// <ul>
+    ///     <li>no items are allowed into the store; items are held outside until the OIOO reopens</li>
+    /// </ul>
+    ///
+    /// <b>Using Phase Custom</b>
+    /// <ul>
+    ///     <li>capacity is set to the passed in Phase::Custom's ratio of its occupancy value</li>
+    /// </ul>
+    ///
+    /// Fractional capacities are rounded down; use `OIOOBuilder::rounding` to change this.
     pub fn new(phase: Phase) -> OIOO<T> {
-        let capacity = match phase {
-            // Phase One 25% occupancy for essentials 
-            Phase::One { occupancy, is_essential } => {
-                if is_essential { occupancy / 4 } else { 0 }
-            },
-            // Phase Two 50% occupancy regardless of essentiality
-            Phase::Two { occupancy } => occupancy / 2
-        };
+        OIOO::with_rng(phase, StdRng::from_entropy())
+    }
+}
+
+impl<T, R: Rng + SeedableRng> OIOO<T, R> {
+    /// Creates a new instance of an OIOO based on the selected Phase, using a random
+    /// number generator seeded with the passed in value. Two instances created with the
+    /// same seed will return items in the same order given the same sequence of calls.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// # extern crate rand;
+    /// use rand::rngs::StdRng;
+    ///
+    /// let mut oioo_1 = oioo::OIOO::<usize, StdRng>::with_seed(oioo::Phase::Two { occupancy: 10 }, 42); 
+    /// let mut oioo_2 = oioo::OIOO::<usize, StdRng>::with_seed(oioo::Phase::Two { occupancy: 10 }, 42); 
+    /// for x in 0..5 {
+    ///     oioo_1.one_in(x);
+    ///     oioo_2.one_in(x);
+    /// }
+    /// assert_eq!(oioo_1.one_out(), oioo_2.one_out());
+    /// ```
+    pub fn with_seed(phase: Phase, seed: u64) -> OIOO<T, R> {
+        OIOO::with_rng(phase, R::seed_from_u64(seed))
+    }
+
+    /// Replaces the random number generator with one seeded with the passed in value. Items
+    /// already in the store are unaffected; only the order of future exits is changed.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = R::seed_from_u64(seed);
+    }
+}
 
-        OIOO {
-            store: Vec::<Option<T>>::with_capacity(capacity * SOCIAL_DISTANCE),
-            queue: Vec::<T>::new(),
-            capacity: capacity
+impl<T, R: Rng> OIOO<T, R> {
+    /// Creates a new instance of an OIOO based on the selected Phase which uses the passed
+    /// in random number generator to decide which item leaves the store. See `OIOO::new`
+    /// for how each Phase affects the capacity of the OIOO.
+    pub fn with_rng(phase: Phase, rng: R) -> OIOO<T, R> {
+        OIOOBuilder::new(phase).rng(rng).build()
+    }
+
+    /// Moves the OIOO into a new Phase, recomputing its capacity while items remain inside.
+    /// If capacity grows, items waiting in the queue are admitted right away. If capacity
+    /// shrinks, surplus items are handled according to the OIOO's Eviction policy, which
+    /// defaults to <b>Eviction::AwaitExits</b>. Waiting items the new Phase no longer allows
+    /// into the store are moved to the held line, and held items it does allow join the
+    /// back of the queue.
+    ///
+    /// Items moved into the queue are subject to its maximum length the same way as
+    /// arriving items; any item the Overflow policy turns away or drops is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::One { occupancy: 4, is_essential: true }); 
+    /// oioo.one_in(10); // contained in store
+    /// oioo.one_in(20); // exceeds storage, gets contained in outer queue
+    ///
+    /// oioo.set_phase(oioo::Phase::Two { occupancy: 4 }); // 20 is moved into the store
+    /// ```
+    pub fn set_phase(&mut self, phase: Phase) -> Vec<T> {
+        self.set_policy(phase)
+    }
+
+    /// Replaces the policy limiting the capacity of the OIOO, handling items inside and
+    /// waiting the same way as `set_phase`.
+    pub fn set_policy<P: CapacityPolicy<T> + Send + 'static>(&mut self, policy: P) -> Vec<T> {
+        self.policy = Box::new(policy);
+        self.capacity_override = None;
+        let mut turned_away = Vec::<T>::new();
+
+        let venue = self.venue();
+        let policy = &self.policy;
+        let (queue, no_longer_admitted) = self.queue.drain(..)
+                                                    .partition::<VecDeque<_>, _>(|e| policy.may_enter(&e.item, e.essentiality, &venue));
+        let (now_admitted, held) = self.held.drain(..)
+                                            .partition::<Vec<_>, _>(|e| policy.may_enter(&e.item, e.essentiality, &venue));
+        self.queue = queue;
+        self.held = held.into();
+        self.held.extend(no_longer_admitted);
+
+        let capacity = self.capacity();
+        if self.eviction == Eviction::ToQueueFront && self.store.len() > capacity {
+            let mut by_admission = (0..self.store.len()).collect::<Vec<_>>();
+            by_admission.sort_by_key(|&i| self.store[i].admitted);
+            let mut surplus = by_admission.split_off(capacity);
+            surplus.sort_unstable();
+
+            let mut evicted = self.remove_all(surplus);
+            evicted.sort_by_key(|o| o.admitted);
+            let mut front = 0;
+            for occupant in evicted {
+                if !self.may_enter(&occupant.entry) {
+                    self.held.push_back(occupant.entry);
+                    continue;
+                }
+                match self.enqueue(occupant.entry, front) {
+                    Ok((position, dropped)) => {
+                        front = position + 1;
+                        turned_away.extend(dropped.map(|e| e.item));
+                        for observer in self.observers.iter_mut() {
+                            observer.queued(&self.queue[position].item, position);
+                        }
+                    },
+                    Err(entry) => turned_away.push(entry.item)
+                }
+            }
+        }
+
+        self.admit_from_queue();
+        for entry in now_admitted {
+            match self.admit_entry(entry) {
+                Ok(Admission::Admitted { .. }) => {},
+                Ok(Admission::Queued { dropped, .. }) => turned_away.extend(dropped),
+                Err(item) => turned_away.push(item)
+            }
         }
+
+        turned_away
+    }
+
+    /// Adds an observer to be notified as items move through the OIOO.
+    pub fn add_observer<O: OIOOObserver<T> + Send + 'static>(&mut self, observer: O) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Sets the policy applied to surplus items when set_phase reduces capacity.
+    pub fn set_eviction(&mut self, eviction: Eviction) {
+        self.eviction = eviction;
     }
 
     /// Pushes an item into the store if there is space. If the store is
@@ -62,6 +318,9 @@ impl<T> OIOO<T> {
     /// the store will have an appropriate amount of social distance between
     /// it and the next item added to the store.
     ///
+    /// If the queue has a maximum length and is full, its Overflow policy decides
+    /// which item is turned away; that item is returned.
+    ///
     /// # Example
     ///
     /// ```
@@ -71,19 +330,395 @@ impl<T> OIOO<T> {
     /// oioo.one_in(10); // contained in store
     /// oioo.one_in(20); // exceeds storage, gets contained in outer queue
     /// ```
-    pub fn one_in(self: &mut Self, item: T) {
-        if !self.at_capacity() {
-            self.store.push(Some(item));
-            self.add_social_distance();
+    pub fn one_in(self: &mut Self, item: T) -> Option<T> {
+        let entry = self.entry(item, None);
+        self.push_entry(entry)
+    }
+
+    /// Pushes an item with its own essentiality into the OIOO. The item is handled the
+    /// same way as `one_in`, except during Phase One where a non-essential item is held
+    /// in a separate line instead of entering the store or queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// use oioo::Essentiality;
+    ///
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::One { occupancy: 8, is_essential: true }); 
+    /// oioo.one_in_with(10, Essentiality::Essential); // contained in store
+    /// oioo.one_in_with(20, Essentiality::NonEssential); // held until Phase Two
+    ///
+    /// assert_eq!(oioo.one_out(), Some(10));
+    /// assert_eq!(oioo.one_out(), None);
+    /// ```
+    pub fn one_in_with(&mut self, item: T, essentiality: Essentiality) -> Option<T> {
+        let entry = self.entry(item, Some(essentiality));
+        self.push_entry(entry)
+    }
+
+    /// Pushes an item into the OIOO the same way as `one_in`, reporting whether it was
+    /// admitted into the store or queued, along with a Ticket for the item. Instead of being
+    /// held outside, an item the current Phase does not allow into the store, including any
+    /// item while the OIOO is Closed, is handed back inside the error, as is an item
+    /// turned away by a full queue using <b>Overflow::RejectNewcomer</b>. A waiting item
+    /// dropped to make room for this one is handed back inside the Admission.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// use oioo::{ Admission, Rejected };
+    ///
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::Two { occupancy: 2 }); 
+    /// assert!(matches!(oioo.try_one_in(10), Ok(Admission::Admitted { .. })));
+    /// assert!(matches!(oioo.try_one_in(20), Ok(Admission::Queued { position: 0, dropped: None, .. })));
+    ///
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::One { occupancy: 8, is_essential: false }); 
+    /// assert_eq!(oioo.try_one_in(10), Err(Rejected::NonEssential(10)));
+    /// ```
+    pub fn try_one_in(&mut self, item: T) -> Result<Admission<T>, Rejected<T>> {
+        let entry = self.entry(item, None);
+        self.try_push_entry(entry)
+    }
+
+    /// Pushes an item with its own essentiality into the OIOO, reporting the outcome the same
+    /// way as `try_one_in`.
+    pub fn try_one_in_with(&mut self, item: T, essentiality: Essentiality) -> Result<Admission<T>, Rejected<T>> {
+        let entry = self.entry(item, Some(essentiality));
+        self.try_push_entry(entry)
+    }
+
+    /// Removes the item the Ticket was issued for, whether it is inside the store, waiting
+    /// in the queue, held outside or isolated. Space it leaves in the store is filled from the queue
+    /// as it is by `one_out`. Returns None if the item is already gone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// use oioo::Status;
+    ///
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::Two { occupancy: 2 }); 
+    /// let inside = oioo.try_one_in(10).unwrap().ticket();
+    /// let waiting = oioo.try_one_in(20).unwrap().ticket();
+    /// assert_eq!(oioo.status(waiting), Status::Waiting);
+    /// assert_eq!(oioo.position(waiting), Some(0));
+    ///
+    /// assert_eq!(oioo.leave(inside), Some(10));
+    /// assert_eq!(oioo.status(inside), Status::Gone);
+    /// assert_eq!(oioo.status(waiting), Status::Inside); // admitted into the space left behind
+    /// ```
+    pub fn leave(&mut self, ticket: Ticket) -> Option<T> {
+        if let Some(index) = self.store.iter().position(|o| o.entry.ticket == ticket) {
+            let out = self.remove_at(index);
+            self.admit_from_queue();
+            return Some(out.entry.item);
+        }
+
+        let waiting = self.queue.iter().position(|e| e.ticket == ticket);
+        if let Some(index) = waiting {
+            return self.queue.remove(index).map(|e| e.item);
+        }
+
+        let held = self.held.iter().position(|e| e.ticket == ticket);
+        if let Some(index) = held {
+            return self.held.remove(index).map(|e| e.item);
+        }
+
+        self.release(ticket)
+    }
+
+    /// Number of items ahead of the Ticket's item in the queue in arrival order, so 0 means
+    /// it is at the front. Only under the default Fifo discipline is the front item the next
+    /// to be admitted. Returns None if the item is not waiting in the queue.
+    pub fn position(&self, ticket: Ticket) -> Option<usize> {
+        self.queue.iter().position(|e| e.ticket == ticket)
+    }
+
+    /// Returns where the item the Ticket was issued for currently is.
+    pub fn status(&self, ticket: Ticket) -> Status {
+        if self.store.iter().any(|o| o.entry.ticket == ticket) {
+            Status::Inside
+        } else if self.queue.iter().any(|e| e.ticket == ticket) {
+            Status::Waiting
+        } else if self.held.iter().any(|e| e.ticket == ticket) {
+            Status::Held
+        } else if self.isolated.iter().any(|e| e.ticket == ticket) {
+            Status::Isolated
         } else {
-            self.queue.push(item);
+            Status::Gone
         }
     }
 
+    /// Isolates the Ticket's item along with every item in the store within <b>radius</b>
+    /// positions of it, measured with the FloorPlan's metric or along the line when there
+    /// is no FloorPlan. Isolated items stay in the OIOO, apart from everyone else, until
+    /// they are released; waiting items are admitted into the space they leave in the
+    /// store. An item that is waiting or held outside is isolated alone. Returns the
+    /// tickets of the isolated items, the flagged item first and the rest in the order they
+    /// were admitted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// use oioo::Status;
+    ///
+    /// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 6 })
+    ///     .social_distance(1)
+    ///     .build();
+    /// let tickets = (0..4).map(|x| oioo.try_one_in(x).unwrap().ticket()).collect::<Vec<_>>();
+    ///
+    /// // 0 sits at position 0, 1 at position 2 and 2 at position 4
+    /// assert_eq!(oioo.quarantine(tickets[0], 2), vec![tickets[0], tickets[1]]);
+    /// assert_eq!(oioo.status(tickets[1]), Status::Isolated);
+    /// assert_eq!(oioo.status(tickets[3]), Status::Inside); // admitted from the queue
+    ///
+    /// assert_eq!(oioo.release(tickets[1]), Some(1));
+    /// assert_eq!(oioo.release_all(), vec![0]);
+    /// ```
+    pub fn quarantine(&mut self, ticket: Ticket, radius: usize) -> Vec<Ticket> {
+        let flagged = match self.store.iter().find(|o| o.entry.ticket == ticket) {
+            Some(occupant) => occupant.position,
+            None => {
+                let waiting = self.queue.iter().position(|e| e.ticket == ticket);
+                let held = self.held.iter().position(|e| e.ticket == ticket);
+                let entry = match (waiting, held) {
+                    (Some(index), _) => self.queue.remove(index),
+                    (None, Some(index)) => self.held.remove(index),
+                    (None, None) => return Vec::<Ticket>::new()
+                };
+                self.isolated.extend(entry);
+                return vec![ticket];
+            }
+        };
+
+        let metric = self.floor_plan.as_ref().map_or(Metric::Manhattan, |f| f.metric());
+        let nearby = (0..self.store.len()).filter(|&i| metric.reaches(flagged, self.store[i].position, radius))
+                                          .collect::<Vec<_>>();
+        let mut removed = self.remove_all(nearby);
+        removed.sort_by_key(|o| (o.entry.ticket != ticket, o.admitted));
+
+        let tickets = removed.iter().map(|o| o.entry.ticket).collect();
+        self.isolated.extend(removed.into_iter().map(|o| o.entry));
+        self.admit_from_queue();
+
+        tickets
+    }
+
+    /// Releases the Ticket's item from isolation, handing it back. Returns None if the item
+    /// is not isolated.
+    pub fn release(&mut self, ticket: Ticket) -> Option<T> {
+        let index = self.isolated.iter().position(|e| e.ticket == ticket)?;
+        self.isolated.remove(index).map(|e| e.item)
+    }
+
+    /// Releases every isolated item, in the order they were isolated.
+    pub fn release_all(&mut self) -> Vec<T> {
+        self.isolated.drain(..).map(|e| e.item).collect()
+    }
+
+    /// Number of items isolated by `quarantine`.
+    pub fn isolated_len(&self) -> usize {
+        self.isolated.len()
+    }
+
+    /// Iterates over the isolated items, in the order they were isolated.
+    pub fn isolated(&self) -> impl Iterator<Item = &T> + '_ {
+        self.isolated.iter().map(|e| &e.item)
+    }
+
+    /// Returns the seat the Ticket's item sits in, or None if it is not inside the store.
+    pub fn seat_of(&self, ticket: Ticket) -> Option<SeatId> {
+        self.store.iter().find(|o| o.entry.ticket == ticket).map(|o| o.seat)
+    }
+
+    /// Total number of items in the OIOO, whether inside the store, waiting in the queue,
+    /// held outside or isolated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::Two { occupancy: 4 }); 
+    /// oioo.extend(vec![10, 20, 30]);
+    ///
+    /// assert_eq!(oioo.len(), 3);
+    /// assert_eq!(oioo.store_len(), 2);
+    /// assert_eq!(oioo.queue_len(), 1);
+    /// assert_eq!(oioo.capacity(), 2);
+    /// assert!(oioo.is_full());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.store.len() + self.queue.len() + self.held.len() + self.isolated.len()
+    }
+
+    /// Returns true if there are no items in the store, queue, held line or isolation.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of items inside the store.
+    pub fn store_len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Number of items waiting in the queue for space in the store.
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Number of items allowed in the store under the current Phase or policy.
+    pub fn capacity(&self) -> usize {
+        self.capacity_override.unwrap_or_else(|| self.policy.allowed_capacity(&self.venue()))
+    }
+
+    /// Returns true if the store holds as many items as its capacity allows, or if its
+    /// FloorPlan has no cell left that keeps distance from everyone inside.
+    pub fn is_full(&self) -> bool {
+        self.next_seat().is_none()
+    }
+
+    /// Record of which items sat within range of each other, if the OIOO was built with
+    /// `OIOOBuilder::contact_log`.
+    pub fn contact_log(&self) -> Option<&ContactLog> {
+        self.contact_log.as_ref()
+    }
+
+    /// Exports the contact graph as `ContactLog::graph` does, counting contacts still
+    /// ongoing up to now. Empty if the OIOO keeps no contact log.
+    pub fn contact_graph(&self) -> Vec<(Ticket, Ticket, Duration)> {
+        self.contact_log.as_ref()
+                        .map(|log| log.graph(self.clock.now()))
+                        .unwrap_or_default()
+    }
+
+    /// Sets the risk modifier `rank_exposed` passes to its RiskModel for the Ticket's item,
+    /// such as a lower value for an item wearing a mask. Has no effect if the OIOO keeps no
+    /// contact log.
+    pub fn set_risk_modifier(&mut self, ticket: Ticket, modifier: f64) {
+        if let Some(ref mut contact_log) = self.contact_log {
+            contact_log.set_modifier(ticket, modifier);
+        }
+    }
+
+    /// Ranks the items that were in contact with the flagged item, most exposed first, as
+    /// `ContactLog::rank_exposed` does with contacts still ongoing counted up to now. Empty
+    /// if the OIOO keeps no contact log.
+    pub fn rank_exposed(&self, flagged: Ticket, model: &dyn RiskModel) -> Vec<(Ticket, f64)> {
+        self.contact_log.as_ref()
+                        .map(|log| log.rank_exposed(flagged, model, self.clock.now()))
+                        .unwrap_or_default()
+    }
+
+    /// Phase currently limiting the capacity of the OIOO, or None if it is limited by some
+    /// other CapacityPolicy.
+    pub fn phase(&self) -> Option<Phase> {
+        self.policy.phase()
+    }
+
+    /// Number of empty spaces kept between items in the store.
+    pub fn social_distance(&self) -> usize {
+        self.social_distance
+    }
+
+    /// Iterates over the items inside the store, in no particular order.
+    pub fn inside(&self) -> impl Iterator<Item = &T> + '_ {
+        self.store.iter().map(|o| &o.entry.item)
+    }
+
+    /// Iterates over the items inside the store along with their positions, in no
+    /// particular order.
+    pub fn seated(&self) -> impl Iterator<Item = (Position, &T)> + '_ {
+        self.store.iter().map(|o| (o.position, &o.entry.item))
+    }
+
+    /// Iterates over the items inside the store along with their seats, in no particular
+    /// order.
+    pub fn seats(&self) -> impl Iterator<Item = (SeatId, &T)> + '_ {
+        self.store.iter().map(|o| (o.seat, &o.entry.item))
+    }
+
+    /// Returns the item sitting in the passed in seat, or None if it has left the store.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// use oioo::Position;
+    ///
+    /// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 6 })
+    ///     .social_distance(1)
+    ///     .build();
+    /// let ticket = oioo.try_one_in(10).unwrap().ticket();
+    /// let seat = oioo.seat_of(ticket).unwrap();
+    /// oioo.extend(vec![20, 30]);
+    ///
+    /// assert_eq!(oioo.get(seat), Some(&10));
+    /// assert_eq!(oioo.seat_position(seat), Some(Position::new(0, 0)));
+    ///
+    /// while oioo.get(seat).is_some() {
+    ///     oioo.one_out();
+    /// }
+    /// assert_eq!(oioo.seat_position(seat), None);
+    /// ```
+    pub fn get(&self, seat: SeatId) -> Option<&T> {
+        self.seats.get(seat).map(|i| &self.store[i].entry.item)
+    }
+
+    /// Returns where the passed in seat is, or None if its item has left the store.
+    pub fn seat_position(&self, seat: SeatId) -> Option<Position> {
+        self.seats.get(seat).map(|i| self.store[i].position)
+    }
+
+    /// Room the store is laid out on, if the OIOO was built with one.
+    pub fn floor_plan(&self) -> Option<&FloorPlan> {
+        self.floor_plan.as_ref()
+    }
+
+    /// Returns the item at the front of the queue, the one with no items ahead of it in
+    /// arrival order. It is the next to be admitted under the default Fifo discipline;
+    /// other disciplines may pick a different item.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::Two { occupancy: 2 }); 
+    /// oioo.one_in(10); // contained in store
+    /// oioo.one_in(20); // contained in queue, at the front
+    /// oioo.one_in(30); // contained in queue
+    ///
+    /// assert_eq!(oioo.peek_next_in_line(), Some(&20)); // next to be admitted under Fifo
+    /// assert_eq!(oioo.waiting().collect::<Vec<_>>(), vec![&20, &30]);
+    /// ```
+    pub fn peek_next_in_line(&self) -> Option<&T> {
+        self.queue.front().map(|e| &e.item)
+    }
+
+    /// Iterates over the items waiting in the queue, in the order they arrived.
+    pub fn waiting(&self) -> impl Iterator<Item = &T> + '_ {
+        self.queue.iter().map(|e| &e.item)
+    }
+
+    /// Number of items held outside because the current Phase does not allow them in.
+    pub fn held_len(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Iterates over the items held outside, in the order they arrived.
+    pub fn held(&self) -> impl Iterator<Item = &T> + '_ {
+        self.held.iter().map(|e| &e.item)
+    }
+
     /// Returns a random item from the store if one exists. If the store was
     /// at capacity prior to the call, item will be contained "outside" in a queue that will
     /// be pulled from once space becomes available.
     ///
+    /// Items are picked uniformly at random unless the OIOO was built with a different
+    /// ExitStrategy.
+    ///
     /// # Example
     ///
     /// ```
@@ -105,38 +740,357 @@ impl<T> OIOO<T> {
     pub fn one_out(self: &mut Self) -> Option<T> {
         if self.store.len() == 0 { return None; }
 
-        let mut rng = rand::thread_rng();
-        let out_index = rng.gen_range(0, self.store.iter()
-                                                   .filter(|x| x.is_some())
-                                                   .collect::<Vec<_>>()
-                                                   .len()) * (SOCIAL_DISTANCE + 1);
+        let out = self.remove_selected();
+        self.admit_from_queue();
+
+        Some(out.entry.item)
+    }
+
+    /// Removes up to <b>count</b> items from the store one at a time, as if by calling
+    /// `one_out` repeatedly. Items admitted from the queue as space opens up may be among
+    /// those returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::Two { occupancy: 4 }); 
+    /// oioo.extend(vec![10, 20, 30]);
+    ///
+    /// assert_eq!(oioo.one_out_many(2).len(), 2);
+    /// assert_eq!(oioo.one_out_many(2).len(), 1);
+    /// ```
+    pub fn one_out_many(&mut self, count: usize) -> Vec<T> {
+        let mut out = Vec::<T>::with_capacity(count.min(self.store.len()));
+        while out.len() < count {
+            match self.one_out() {
+                Some(item) => out.push(item),
+                None => break
+            }
+        }
+
+        out
+    }
+
+    /// Removes every item that has stayed in the store for at least the OIOO's maximum
+    /// stay, returning them in the order they were admitted. Waiting items are admitted
+    /// into the space left behind. Nothing is removed if the OIOO has no maximum stay.
+    pub fn expire(&mut self) -> Vec<T> {
+        let max_stay = match self.max_stay {
+            Some(max_stay) => max_stay,
+            None => return Vec::<T>::new()
+        };
+
+        let now = self.clock.now();
+        let overstayed = (0..self.store.len()).filter(|&i| now.saturating_sub(self.store[i].since) >= max_stay)
+                                              .collect::<Vec<_>>();
+
+        let mut expired = self.remove_all(overstayed);
+        expired.sort_by_key(|o| o.admitted);
+        self.admit_from_queue();
+
+        expired.into_iter().map(|o| o.entry.item).collect()
+    }
+
+    /// Removes every item from the OIOO, returning store items in exit order, which is
+    /// random unless the OIOO was built with a different ExitStrategy, followed by items
+    /// from the front of the queue and then held items in the order they were held, and finally
+    /// isolated items in the order they were isolated. Items in the
+    /// queue are not admitted into the store as it empties.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// let mut oioo = oioo::OIOO::<usize>::new(oioo::Phase::Two { occupancy: 4 }); 
+    /// oioo.extend(vec![10, 20, 30, 40]);
+    ///
+    /// let drained = oioo.drain().collect::<Vec<_>>();
+    /// assert_eq!(&drained[2..], &[30, 40]); // queued items come out last, in order
+    /// assert_eq!(oioo.one_out(), None);
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, R> {
+        Drain::new(self)
+    }
+
+    /// Removes the next item in drain order without admitting anyone from the queue.
+    fn take_next(&mut self) -> Option<T> {
+        if !self.store.is_empty() {
+            return Some(self.remove_selected().entry.item);
+        }
+
+        self.queue.pop_front()
+                  .or_else(|| self.held.pop_front())
+                  .or_else(|| self.isolated.pop_front())
+                  .map(|e| e.item)
+    }
+
+    /// Removes the occupant chosen by the ExitStrategy. Must only be called when the store
+    /// is not empty.
+    fn remove_selected(&mut self) -> Occupant<T> {
+        let out_index = self.exit_strategy.select(&Room::new(&self.store), &mut self.rng);
+        assert!(out_index < self.store.len(),
+                "ExitStrategy selected index {} but only {} items are in the store", out_index, self.store.len());
+        self.remove_at(out_index)
+    }
+
+    /// Removes the occupant at the passed in index, freeing its seat.
+    fn remove_at(&mut self, index: usize) -> Occupant<T> {
+        // the last item moves into the space left behind, keeping the store contiguous,
+        // but keeps its seat
+        let out = self.store.swap_remove(index);
+        self.seats.vacate(out.seat);
+        if let Some(ref mut contact_log) = self.contact_log {
+            contact_log.leave(out.entry.ticket, self.clock.now());
+        }
+        if let Some(moved) = self.store.get(index) {
+            self.seats.relocate(moved.seat, index);
+        }
+        if let Some(ref mut floor_plan) = self.floor_plan {
+            floor_plan.release(out.position);
+        }
+        for observer in self.observers.iter_mut() {
+            observer.exited(&out.entry.item, out.position);
+        }
+
+        out
+    }
+
+    /// Removes the occupants at the passed in indexes, which must be in ascending order.
+    fn remove_all(&mut self, indexes: Vec<usize>) -> Vec<Occupant<T>> {
+        // removing from the back first keeps the remaining indexes valid
+        indexes.into_iter()
+               .rev()
+               .map(|i| self.remove_at(i))
+               .collect()
+    }
+
+    /// Empties the store without admitting anyone from the queue.
+    fn clear_store(&mut self) {
+        for occupant in &self.store {
+            for observer in self.observers.iter_mut() {
+                observer.exited(&occupant.entry.item, occupant.position);
+            }
+        }
+        self.store.clear();
+        self.seats.clear();
+        if let Some(ref mut contact_log) = self.contact_log {
+            contact_log.leave_all(self.clock.now());
+        }
+        if let Some(ref mut floor_plan) = self.floor_plan {
+            floor_plan.vacate();
+        }
+    }
+
+    /// Returns the seat the next item admitted into the store would take and where it is,
+    /// or None if the store is at capacity or has no cell left that keeps distance.
+    fn next_seat(&self) -> Option<(usize, Position)> {
+        if self.store.len() >= self.capacity() {
+            return None;
+        }
+
+        match self.floor_plan {
+            Some(ref floor_plan) => {
+                let position = match self.placement {
+                    Placement::FirstFit => floor_plan.find_seat(),
+                    Placement::BestFit => floor_plan.find_best_seat()
+                }?;
+                floor_plan.index(position).map(|index| (index, position))
+            },
+            None => {
+                let index = self.seats.vacant(self.placement);
+                Some((index, Position::new(0, index * (self.social_distance + 1))))
+            }
+        }
+    }
+
+    /// Wraps an item pushed into the OIOO, issuing it the next Ticket.
+    fn entry(&mut self, item: T, essentiality: Option<Essentiality>) -> Entry<T> {
+        let ticket = Ticket::new(self.tickets);
+        self.tickets += 1;
+
+        Entry { item, essentiality, ticket }
+    }
+
+    fn may_enter(&self, entry: &Entry<T>) -> bool {
+        self.policy.may_enter(&entry.item, entry.essentiality, &self.venue())
+    }
+
+    /// Describes the OIOO's current state for its CapacityPolicy.
+    fn venue(&self) -> Venue {
+        Venue {
+            social_distance: self.social_distance,
+            rounding: self.rounding,
+            inside: self.store.len(),
+            waiting: self.queue.len(),
+            held: self.held.len()
+        }
+    }
+
+    /// Places an entry in the store, queue or held line, returning any item turned away.
+    fn push_entry(&mut self, entry: Entry<T>) -> Option<T> {
+        if !self.may_enter(&entry) {
+            self.held.push_back(entry);
+            return None;
+        }
+
+        match self.admit_entry(entry) {
+            Ok(Admission::Admitted { .. }) => None,
+            Ok(Admission::Queued { dropped, .. }) => dropped,
+            Err(item) => Some(item)
+        }
+    }
+
+    fn try_push_entry(&mut self, entry: Entry<T>) -> Result<Admission<T>, Rejected<T>> {
+        if !self.may_enter(&entry) {
+            Err(match self.phase() {
+                Some(Phase::Closed) => Rejected::Closed(entry.item),
+                Some(Phase::One { .. }) => Rejected::NonEssential(entry.item),
+                _ => Rejected::Denied(entry.item)
+            })
+        } else {
+            self.admit_entry(entry).map_err(Rejected::QueueFull)
+        }
+    }
 
-        match self.store[out_index].is_some() {
-          true => {
-              let social_distance_index = out_index + SOCIAL_DISTANCE + 1;
-              let mut out_and_social_distance = self.store.drain(out_index..social_distance_index)
-                                                          .collect::<Vec<_>>();
-              if !self.queue.is_empty() {
-                  let first_in_queue = self.queue.remove(0);
-                  self.one_in(first_in_queue);
-              }
+    /// Places an entry the current Phase allows in into the store, or the queue if the
+    /// store is at capacity. If the queue is full, the newcomer is handed back as the
+    /// error unless the Overflow policy drops a waiting item to make room.
+    fn admit_entry(&mut self, entry: Entry<T>) -> Result<Admission<T>, T> {
+        if let Some((index, position)) = self.next_seat() {
+            let ticket = entry.ticket;
+            let seat = self.seat_entry(entry, index, position);
+            let occupant = &self.store[self.store.len() - 1];
+            for observer in self.observers.iter_mut() {
+                observer.admitted(&occupant.entry.item, position);
+            }
+            return Ok(Admission::Admitted { seat, ticket });
+        }
 
-              Some(out_and_social_distance.remove(0).unwrap())
-          }
-          false => None
+        let ticket = entry.ticket;
+        let (position, dropped) = self.enqueue(entry, usize::MAX).map_err(|e| e.item)?;
+        for observer in self.observers.iter_mut() {
+            observer.queued(&self.queue[position].item, position);
         }
+        Ok(Admission::Queued { position, dropped: dropped.map(|e| e.item), ticket })
+    }
+
+    /// Inserts an entry into the queue at <b>index</b>, or at the back if the queue is
+    /// shorter, applying the Overflow policy if the queue is full. Returns where the entry
+    /// ended up along with the waiting entry dropped to make room, or the entry itself as
+    /// the error if it is turned away.
+    fn enqueue(&mut self, entry: Entry<T>, index: usize) -> Result<(usize, Option<Entry<T>>), Entry<T>> {
+        let queue_full = self.max_queue_len.is_some_and(|max| self.queue.len() >= max);
+        let drop_index = match (queue_full, self.overflow) {
+            (false, _) => None,
+            // a queue limited to no one has no waiting item to drop
+            (true, _) if self.queue.is_empty() => return Err(entry),
+            (true, Overflow::RejectNewcomer) => return Err(entry),
+            (true, Overflow::DropOldest) => Some(0),
+            (true, Overflow::DropRandom) => Some(self.rng.gen_range(0, self.queue.len()))
+        };
+
+        let mut index = index;
+        let dropped = drop_index.and_then(|i| {
+            if i < index { index -= 1; }
+            self.queue.remove(i)
+        });
+        let index = index.min(self.queue.len());
+        self.queue.insert(index, entry);
+
+        Ok((index, dropped))
+    }
+
+    /// Places an entry in the passed in seat of the store, returning its SeatId.
+    fn seat_entry(&mut self, entry: Entry<T>, index: usize, position: Position) -> SeatId {
+        if let Some(ref mut floor_plan) = self.floor_plan {
+            floor_plan.take(position);
+        }
+        let seat = self.seats.occupy(index, self.store.len());
+        let now = self.clock.now();
+        if let Some(ref mut contact_log) = self.contact_log {
+            let metric = self.floor_plan.as_ref().map_or(Metric::Manhattan, |f| f.metric());
+            contact_log.enter(entry.ticket, position, metric, now, self.store.iter().map(|o| (o.entry.ticket, o.position)));
+        }
+        self.store.push(Occupant { entry, admitted: self.admissions, since: now, seat, position });
+        self.admissions += 1;
+
+        seat
     }
 
-    fn at_capacity(self: &Self) -> bool {
-        (self.store.len() / (SOCIAL_DISTANCE + 1)) >= self.capacity
+    /// Moves items chosen by the QueueDiscipline from the queue into the store until it is
+    /// full. Chosen items the policy no longer allows in are held instead.
+    fn admit_from_queue(&mut self) {
+        while !self.queue.is_empty() {
+            let (index, position) = match self.next_seat() {
+                Some(seat) => seat,
+                None => break
+            };
+
+            let next_index = self.discipline.select(&Line::new(&self.queue), &mut self.rng);
+            let next_in_queue = match self.queue.remove(next_index) {
+                Some(entry) => entry,
+                None => panic!("QueueDiscipline selected index {} but only {} items are waiting",
+                               next_index, self.queue.len())
+            };
+            if !self.may_enter(&next_in_queue) {
+                self.held.push_back(next_in_queue);
+                continue;
+            }
+
+            self.seat_entry(next_in_queue, index, position);
+            let occupant = &self.store[self.store.len() - 1];
+            for observer in self.observers.iter_mut() {
+                observer.promoted(&occupant.entry.item, position);
+            }
+        }
     }
+}
 
-    fn add_social_distance(self: &mut Self) {
-        for _ in 0..SOCIAL_DISTANCE {
-            self.store.push(None);
+impl<T, R: Rng> Extend<T> for OIOO<T, R> {
+    /// Pushes each item into the OIOO as if by `one_in`. Items turned away by a full
+    /// queue are discarded; use `one_in` directly to get them back.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.one_in(item);
         }
     }
 }
 
+impl<T> std::iter::FromIterator<T> for OIOO<T> {
+    /// Creates an OIOO in Phase Reopened with an occupancy of the number of items, so every
+    /// item is admitted into the store. Use `OIOOBuilder::build_from` to choose the Phase.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// let oioo = (0..5).collect::<oioo::OIOO<usize>>();
+    /// assert_eq!(oioo.store_len(), 5);
+    /// assert_eq!(oioo.phase(), Some(oioo::Phase::Reopened { occupancy: 5 }));
+    /// ```
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OIOO<T> {
+        let items = iter.into_iter().collect::<Vec<_>>();
+        OIOOBuilder::new(Phase::Reopened { occupancy: items.len() }).build_from(items)
+    }
+}
+
+impl<T, R: Rng> IntoIterator for OIOO<T, R> {
+    type Item = T;
+    type IntoIter = IntoIter<T, R>;
+
+    /// Consumes the OIOO, returning items in the same order as `drain`.
+    fn into_iter(self) -> IntoIter<T, R> {
+        IntoIter::new(self)
+    }
+}
+
+/// Fails to compile if the default OIOO stops being `Send` for `Send` items.
+fn _assert_send<T: Send>() {
+    fn is_send<S: Send>() {}
+    is_send::<OIOO<T>>();
+    is_send::<OIOOBuilder<T>>();
+}
+
 #[cfg(test)]
 mod test;
@@ -0,0 +1,47 @@
+use super::Position;
+
+/// Notified as items move through an OIOO, for side effects such as door displays, audit
+/// logs or metrics. Every method does nothing by default, so an observer only implements
+/// the events it cares about. Observers are notified after the OIOO has changed, in the
+/// order they were added.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use std::sync::Arc;
+/// use std::sync::atomic::{ AtomicUsize, Ordering };
+/// use oioo::{ OIOOObserver, Position };
+///
+/// struct Counter(Arc<AtomicUsize>);
+///
+/// impl OIOOObserver<usize> for Counter {
+///     fn admitted(&mut self, _item: &usize, _position: Position) {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let count = Arc::new(AtomicUsize::new(0));
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 4 })
+///     .observer(Counter(count.clone()))
+///     .build();
+/// oioo.extend(vec![10, 20, 30]);
+///
+/// assert_eq!(count.load(Ordering::SeqCst), 2); // 30 was queued
+/// ```
+pub trait OIOOObserver<T> {
+    /// An arriving item was admitted straight into the store at <b>position</b>.
+    fn admitted(&mut self, _item: &T, _position: Position) {}
+
+    /// An item joined the queue with <b>position</b> items ahead of it in arrival order,
+    /// either on arriving or after `set_phase` moved it back out of the store or released
+    /// it from being held.
+    fn queued(&mut self, _item: &T, _position: usize) {}
+
+    /// An item left the store from <b>position</b>, whether through `one_out` or any other
+    /// way out such as `leave`, `expire`, `quarantine` or `drain`.
+    fn exited(&mut self, _item: &T, _position: Position) {}
+
+    /// A waiting item was admitted from the queue into the store at <b>position</b>.
+    fn promoted(&mut self, _item: &T, _position: Position) {}
+}
@@ -0,0 +1,191 @@
+use super::{ FloorPlan, Position };
+
+/// Amount of work `FloorPlan::pack` spends looking for the exact maximum, counted in
+/// candidate cells looked at. Enough to prove an 8 by 8 room exact while keeping a 100 by
+/// 100 room to well under a second.
+pub(crate) static PACKING_SEARCH_LIMIT: usize = 1_000_000;
+
+/// A set of positions on a FloorPlan that can all be occupied at once without breaking
+/// distance, as found by `FloorPlan::pack`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use oioo::{ FloorPlan, Metric };
+///
+/// let floor_plan = FloorPlan::grid(4, 5, 3, Metric::Manhattan);
+/// assert_eq!(floor_plan.capacity(), 4); // seating each item in the first free cell
+///
+/// let packing = floor_plan.pack();
+/// assert!(packing.is_exact());
+/// assert_eq!(packing.occupancy(), 5);
+///
+/// let mut oioo = oioo::OIOOBuilder::from_floor_plan(floor_plan.seated_at(&packing)).build();
+/// oioo.extend(0..6);
+/// assert_eq!(oioo.store_len(), 5);
+/// assert!(oioo.seated().all(|(position, _)| packing.positions().contains(&position)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Packing {
+    positions: Vec<Position>,
+    exact: bool
+}
+
+impl Packing {
+    /// Positions in the packing, in row-major order.
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    /// Number of items the packing seats.
+    pub fn occupancy(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether the search finished, proving no larger packing exists. Otherwise this is the
+    /// best packing found before the search limit was reached, which may or may not be the
+    /// largest.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// Finds the largest set of usable cells where no two are closer than the floor plan's
+    /// radius, doing at most <b>limit</b> work.
+    pub(crate) fn solve(floor_plan: &FloorPlan, limit: usize) -> Packing {
+        let cells = floor_plan.positions()
+                              .filter(|p| floor_plan.is_usable(*p))
+                              .collect::<Vec<_>>();
+        let mut index = vec![None; floor_plan.rows() * floor_plan.columns()];
+        cells.iter().enumerate().for_each(|(i, p)| index[p.row * floor_plan.columns() + p.column] = Some(i));
+
+        let reach = floor_plan.radius().saturating_sub(1);
+        let neighbors = cells.iter().map(|&cell| {
+            let rows = cell.row.saturating_sub(reach)..(cell.row + reach + 1).min(floor_plan.rows());
+            let columns = cell.column.saturating_sub(reach)..(cell.column + reach + 1).min(floor_plan.columns());
+            rows.flat_map(|row| columns.clone().map(move |column| Position::new(row, column)))
+                .filter(|&other| other != cell && floor_plan.metric().within(cell, other, floor_plan.radius()))
+                .filter_map(|other| index[other.row * floor_plan.columns() + other.column])
+                .collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+
+        let mut solver = Solver::new(&neighbors, limit);
+        solver.search(&mut Vec::<usize>::new(), (0..cells.len()).collect());
+
+        let mut positions = solver.best.iter().map(|&i| cells[i]).collect::<Vec<_>>();
+        positions.sort();
+
+        Packing { positions, exact: !solver.exhausted }
+    }
+}
+
+/// Takes every cell that doesn't conflict with one already taken, in row-major order.
+fn first_fit(neighbors: &[Vec<usize>]) -> Vec<usize> {
+    let mut blocked = vec![false; neighbors.len()];
+    let mut taken = Vec::<usize>::new();
+    for i in 0..neighbors.len() {
+        if !blocked[i] {
+            taken.push(i);
+            neighbors[i].iter().for_each(|&j| blocked[j] = true);
+        }
+    }
+
+    taken
+}
+
+/// Branch and bound search for a maximum independent set of the conflict graph, where two
+/// cells conflict if they are closer than the radius. Every step is charged for each
+/// candidate it looks at, so the limit bounds the total work rather than the number of
+/// branches.
+struct Solver<'a> {
+    neighbors: &'a [Vec<usize>],
+    best: Vec<usize>,
+    steps: usize,
+    limit: usize,
+    exhausted: bool,
+    /// Scratch marks indexed by cell, always left all false between uses.
+    marked: Vec<bool>,
+    /// Scratch clique of each cell, always left all None between uses.
+    clique_of: Vec<Option<usize>>
+}
+
+impl<'a> Solver<'a> {
+    fn new(neighbors: &'a [Vec<usize>], limit: usize) -> Solver<'a> {
+        Solver {
+            neighbors,
+            best: first_fit(neighbors),
+            steps: 0,
+            limit,
+            exhausted: false,
+            marked: vec![false; neighbors.len()],
+            clique_of: vec![None; neighbors.len()]
+        }
+    }
+
+    fn search(&mut self, chosen: &mut Vec<usize>, candidates: Vec<usize>) {
+        if self.steps >= self.limit {
+            self.exhausted = true;
+            return;
+        }
+        self.steps += candidates.len() + 1;
+
+        if candidates.is_empty() {
+            if chosen.len() > self.best.len() {
+                self.best = chosen.clone();
+            }
+            return;
+        }
+
+        if chosen.len() + self.clique_cover(&candidates) <= self.best.len() {
+            return;
+        }
+
+        // some largest packing holds either the least crowded cell or one of its neighbors
+        candidates.iter().for_each(|&c| self.marked[c] = true);
+        let cell = *candidates.iter()
+                              .min_by_key(|&&c| self.neighbors[c].iter().filter(|&&n| self.marked[n]).count())
+                              .unwrap();
+        let branches = std::iter::once(cell)
+                           .chain(self.neighbors[cell].iter().copied().filter(|&n| self.marked[n]))
+                           .collect::<Vec<_>>();
+        candidates.iter().for_each(|&c| self.marked[c] = false);
+
+        for (i, &branch) in branches.iter().enumerate() {
+            // earlier branches already covered packings holding those cells
+            let excluded = branches[..=i].iter().chain(self.neighbors[branch].iter()).copied().collect::<Vec<_>>();
+            excluded.iter().for_each(|&e| self.marked[e] = true);
+            let remaining = candidates.iter().copied().filter(|&c| !self.marked[c]).collect();
+            excluded.iter().for_each(|&e| self.marked[e] = false);
+
+            chosen.push(branch);
+            self.search(chosen, remaining);
+            chosen.pop();
+
+            if self.exhausted {
+                return;
+            }
+        }
+    }
+
+    /// Upper bound on how many of the candidates can be packed: the number of groups in a
+    /// greedy split into groups that all conflict with each other. A cell only ever joins
+    /// the group of one of its neighbors, so the split takes time in proportion to the
+    /// candidates and their neighbors rather than to the number of groups.
+    fn clique_cover(&mut self, candidates: &[usize]) -> usize {
+        let mut cliques = Vec::<Vec<usize>>::new();
+        for &c in candidates {
+            let neighbors = &self.neighbors[c];
+            let joined = neighbors.iter()
+                                  .filter_map(|&n| self.clique_of[n])
+                                  .find(|&k| cliques[k].iter().all(|m| neighbors.binary_search(m).is_ok()));
+            match joined {
+                Some(k) => cliques[k].push(c),
+                None => cliques.push(vec![c])
+            }
+            self.clique_of[c] = joined.or(Some(cliques.len() - 1));
+        }
+        candidates.iter().for_each(|&c| self.clique_of[c] = None);
+
+        cliques.len()
+    }
+}
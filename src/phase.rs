@@ -0,0 +1,121 @@
+/// Dictates the current Phase, which limits the capabilities of an OIOO instance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    /// <b>is_essential</b> is the essentiality given to items pushed with `one_in`;
+    /// items pushed with `one_in_with` carry their own.
+    One { 
+        occupancy: usize, 
+        is_essential: bool 
+    },
+    Two { occupancy: usize },
+    Three { occupancy: usize },
+    /// Full occupancy; distancing is the only remaining restriction.
+    Reopened { occupancy: usize },
+    /// No items are allowed into the store.
+    Closed,
+    /// <b>ratio</b> is the fraction of <b>occupancy</b> allowed in, e.g. 0.4 for 40%.
+    Custom { occupancy: usize, ratio: f64 }
+}
+
+impl Phase {
+    /// Number of items allowed in the store under this Phase, with fractional capacities
+    /// resolved by the passed in Rounding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate oioo;
+    /// use oioo::{ Phase, Rounding };
+    ///
+    /// let phase = Phase::One { occupancy: 3, is_essential: true };
+    /// assert_eq!(phase.capacity(Rounding::Floor), 0);
+    /// assert_eq!(phase.capacity(Rounding::AtLeastOne), 1);
+    /// assert_eq!(Phase::Custom { occupancy: 10, ratio: 0.35 }.capacity(Rounding::Nearest), 4);
+    /// ```
+    pub fn capacity(&self, rounding: Rounding) -> usize {
+        // fixed ratios are worked out exactly as numerator / denominator
+        let (occupancy, numerator, denominator) = match *self {
+            // Phase One 25% occupancy for essentials 
+            Phase::One { occupancy, .. } => (occupancy, 1, 4),
+            // Phase Two 50% occupancy regardless of essentiality
+            Phase::Two { occupancy } => (occupancy, 1, 2),
+            Phase::Three { occupancy } => (occupancy, 3, 4),
+            Phase::Reopened { occupancy } => (occupancy, 1, 1),
+            Phase::Closed => (0, 0, 1),
+            Phase::Custom { occupancy, ratio } => return rounding.apply(occupancy as f64 * ratio.max(0.0))
+        };
+
+        rounding.apply_exact(occupancy as u128 * numerator, denominator)
+    }
+
+    /// Whether an item of the given essentiality may enter the store under this Phase. Items
+    /// without an essentiality of their own follow Phase One's <b>is_essential</b>.
+    pub(crate) fn admits(&self, essentiality: Option<Essentiality>) -> bool {
+        match *self {
+            Phase::One { is_essential, .. } => {
+                essentiality.map_or(is_essential, |e| e == Essentiality::Essential)
+            },
+            Phase::Closed => false,
+            _ => true
+        }
+    }
+}
+
+/// Dictates how a Phase's percentage of occupancy is turned into a whole capacity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rounding {
+    /// Rounds down. This is the default.
+    Floor,
+    /// Rounds up.
+    Ceil,
+    /// Rounds to the nearest whole number, with halves rounded up.
+    Nearest,
+    /// Rounds down, but never below one unless the Phase allows no one in at all.
+    AtLeastOne
+}
+
+/// How close a fractional capacity must be to a whole or half number to be treated as
+/// one, so that e.g. 100 * 0.29 is 29 rather than 28.999999999999996.
+const ROUNDING_TOLERANCE: f64 = 1e-9;
+
+impl Rounding {
+    fn apply(&self, capacity: f64) -> usize {
+        let halves = (capacity * 2.0).round();
+        let capacity = if (capacity * 2.0 - halves).abs() <= ROUNDING_TOLERANCE * halves.abs().max(1.0) {
+            halves / 2.0
+        } else {
+            capacity
+        };
+
+        match *self {
+            Rounding::Floor => capacity.floor() as usize,
+            Rounding::Ceil => capacity.ceil() as usize,
+            Rounding::Nearest => capacity.round() as usize,
+            Rounding::AtLeastOne => {
+                if capacity > 0.0 { (capacity.floor() as usize).max(1) } else { 0 }
+            }
+        }
+    }
+
+    /// Rounds <b>numerator / denominator</b> without going through floating point.
+    fn apply_exact(&self, numerator: u128, denominator: u128) -> usize {
+        let capacity = match *self {
+            Rounding::Floor => numerator / denominator,
+            Rounding::Ceil => numerator.div_ceil(denominator),
+            Rounding::Nearest => (2 * numerator + denominator) / (2 * denominator),
+            Rounding::AtLeastOne => {
+                if numerator > 0 { (numerator / denominator).max(1) } else { 0 }
+            }
+        };
+
+        capacity as usize
+    }
+}
+
+/// Whether an individual item is essential. During Phase One only essential items
+/// are allowed into the store.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Essentiality {
+    Essential,
+    NonEssential
+}
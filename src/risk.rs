@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+/// What a RiskModel knows about one contact between a flagged item and another item.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Exposure {
+    /// Distance between the two items, in positions.
+    pub distance: f64,
+    /// How long the two items were in contact.
+    pub duration: Duration,
+    /// Modifier of the flagged item, such as how well its mask contains spread.
+    pub source_modifier: f64,
+    /// Modifier of the exposed item, such as how well its mask protects it.
+    pub exposed_modifier: f64
+}
+
+/// Scores how much an item was exposed to a flagged item over a single contact.
+/// `OIOO::rank_exposed` adds up the scores of every contact an item had with the flagged
+/// item, so a score of zero means no risk.
+pub trait RiskModel {
+    fn score(&self, exposure: &Exposure) -> f64;
+}
+
+/// Scores exposure as the minutes in contact divided by one plus the squared distance,
+/// scaled by both items' modifiers and the ventilation of the venue.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use std::time::Duration;
+/// use oioo::{ InverseSquare, ManualClock };
+///
+/// let clock = ManualClock::new();
+/// let mut oioo = oioo::OIOOBuilder::new(oioo::Phase::Two { occupancy: 6 })
+///     .social_distance(0)
+///     .contact_log(2)
+///     .clock(clock.clone())
+///     .build();
+/// let flagged = oioo.try_one_in(10).unwrap().ticket(); // position 0
+/// let near = oioo.try_one_in(20).unwrap().ticket(); // position 1
+/// let far = oioo.try_one_in(30).unwrap().ticket(); // position 2
+/// clock.advance(Duration::from_secs(600));
+///
+/// let ranked = oioo.rank_exposed(flagged, &InverseSquare::new());
+/// assert_eq!(ranked.iter().map(|(t, _)| *t).collect::<Vec<_>>(), vec![near, far]);
+///
+/// // a mask on the nearer item cuts its risk below the other's
+/// oioo.set_risk_modifier(near, 0.1);
+/// let ranked = oioo.rank_exposed(flagged, &InverseSquare::new());
+/// assert_eq!(ranked[0].0, far);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InverseSquare {
+    /// Multiplies every score; below 1 for a well ventilated venue, above 1 for a poorly
+    /// ventilated one.
+    pub ventilation: f64
+}
+
+impl InverseSquare {
+    /// Creates the model with neutral ventilation.
+    pub fn new() -> InverseSquare {
+        InverseSquare { ventilation: 1.0 }
+    }
+}
+
+impl Default for InverseSquare {
+    fn default() -> InverseSquare {
+        InverseSquare::new()
+    }
+}
+
+impl RiskModel for InverseSquare {
+    fn score(&self, exposure: &Exposure) -> f64 {
+        let minutes = exposure.duration.as_secs_f64() / 60.0;
+        minutes * exposure.source_modifier * exposure.exposed_modifier * self.ventilation
+            / (1.0 + exposure.distance * exposure.distance)
+    }
+}
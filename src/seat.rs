@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+
+/// Identifies an item's seat in the store. A SeatId stays valid, and keeps pointing at the
+/// same item in the same position, until that item leaves the store; after that it no longer
+/// refers to anything, even once someone else takes the seat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SeatId {
+    index: usize,
+    generation: u32
+}
+
+/// Dictates which free seat an item admitted into the store takes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Placement {
+    /// The first free seat: the lowest slot in a line, or the first cell in row-major order
+    /// that keeps distance on a FloorPlan.
+    FirstFit,
+    /// The free seat that leaves the most room for later arrivals: in a line, the lowest
+    /// slot of the smallest hole left by earlier exits; on a FloorPlan, the cell that rules
+    /// out the fewest other free cells.
+    BestFit
+}
+
+struct Slot {
+    generation: u32,
+    /// Index in "store" of the occupant sitting here, if any.
+    occupant: Option<usize>
+}
+
+/// Seats of the store, slot-map style: exits leave holes instead of moving anyone, and
+/// a seat's generation changes each time it is vacated so old SeatIds stop resolving.
+pub(crate) struct Seats {
+    slots: Vec<Slot>,
+    /// Vacant seats below "slots.len()"; every seat from "slots.len()" on is also vacant.
+    free: BTreeSet<usize>
+}
+
+impl Seats {
+    pub(crate) fn new() -> Seats {
+        Seats { slots: Vec::<Slot>::new(), free: BTreeSet::<usize>::new() }
+    }
+
+    /// Sits the occupant at the passed in store index in seat <b>index</b>.
+    pub(crate) fn occupy(&mut self, index: usize, occupant: usize) -> SeatId {
+        while self.slots.len() <= index {
+            self.free.insert(self.slots.len());
+            self.slots.push(Slot { generation: 0, occupant: None });
+        }
+
+        self.free.remove(&index);
+        let slot = &mut self.slots[index];
+        slot.occupant = Some(occupant);
+
+        SeatId { index, generation: slot.generation }
+    }
+
+    pub(crate) fn vacate(&mut self, seat: SeatId) {
+        self.vacate_at(seat.index);
+    }
+
+    fn vacate_at(&mut self, index: usize) {
+        let slot = &mut self.slots[index];
+        slot.occupant = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.insert(index);
+    }
+
+    /// Records that the occupant of the seat moved to another index in "store".
+    pub(crate) fn relocate(&mut self, seat: SeatId, occupant: usize) {
+        self.slots[seat.index].occupant = Some(occupant);
+    }
+
+    /// Vacates every seat.
+    pub(crate) fn clear(&mut self) {
+        let occupied = self.slots.iter().enumerate()
+                                 .filter(|(_, s)| s.occupant.is_some())
+                                 .map(|(i, _)| i)
+                                 .collect::<Vec<_>>();
+        occupied.into_iter().for_each(|i| self.vacate_at(i));
+    }
+
+    /// Returns the store index of the occupant the SeatId refers to, if it is still seated.
+    pub(crate) fn get(&self, seat: SeatId) -> Option<usize> {
+        self.slots.get(seat.index)
+                  .filter(|s| s.generation == seat.generation)
+                  .and_then(|s| s.occupant)
+    }
+
+    /// Returns which seat of a line an item should take.
+    pub(crate) fn vacant(&self, placement: Placement) -> usize {
+        match placement {
+            Placement::FirstFit => self.free.iter().next().copied().unwrap_or(self.slots.len()),
+            Placement::BestFit => self.smallest_hole().unwrap_or(self.slots.len())
+        }
+    }
+
+    /// Lowest seat of the smallest run of vacant seats that doesn't reach the end of the
+    /// line, if any.
+    fn smallest_hole(&self) -> Option<usize> {
+        let mut best: Option<(usize, usize)> = None;
+        let mut run: Option<(usize, usize)> = None;
+        for &index in &self.free {
+            run = match run {
+                Some((start, len)) if start + len == index => Some((start, len + 1)),
+                _ => {
+                    best = Self::smaller(best, run);
+                    Some((index, 1))
+                }
+            };
+        }
+        // a run touching the end of the line is open-ended, not a hole
+        if run.is_some_and(|(start, len)| start + len < self.slots.len()) {
+            best = Self::smaller(best, run);
+        }
+
+        best.map(|(start, _)| start)
+    }
+
+    fn smaller(a: Option<(usize, usize)>, b: Option<(usize, usize)>) -> Option<(usize, usize)> {
+        match (a, b) {
+            (Some(a), Some(b)) if b.1 < a.1 => Some(b),
+            (None, b) => b,
+            (a, _) => a
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use rand::{ Rng, SeedableRng };
+use rand::rngs::StdRng;
+
+use super::{ Metric, OIOOBuilder, Phase, Position };
+
+/// Health of a person in a Simulation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Health {
+    Susceptible,
+    Infected,
+    Recovered
+}
+
+/// Result of running a Simulation with one Phase and social distance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outcome {
+    pub phase: Phase,
+    pub social_distance: usize,
+    /// Number of people infected during the run, not counting those infected at the start.
+    pub infections: usize,
+    /// Share of the people susceptible at the start who were infected during the run.
+    pub attack_rate: f64
+}
+
+/// An SIR model of an epidemic spreading among people who visit an OIOO. Each tick, people
+/// arrive from outside with `one_in`, infection spreads between people inside the store
+/// who are within <b>contact_range</b> positions of each other, and people leave with
+/// `one_out` to return outside, so Phases and social distances are compared using the
+/// same admission and exit logic as any other OIOO. Nobody is infected while waiting in
+/// the queue.
+///
+/// Runs are reproducible: the same Simulation run with the same Phase and social distance
+/// always has the same Outcome.
+///
+/// # Example
+///
+/// ```
+/// # extern crate oioo;
+/// use oioo::{ Phase, Simulation };
+///
+/// let simulation = Simulation { transmission: 0.2, ..Simulation::default() };
+/// let phases = [Phase::One { occupancy: 100, is_essential: true }, Phase::Two { occupancy: 100 }];
+/// let outcomes = simulation.sweep(&phases, &[2, 6]);
+/// assert_eq!(outcomes.len(), 4);
+///
+/// for outcome in outcomes {
+///     println!("{:?} at {}: {:.0}%", outcome.phase, outcome.social_distance, outcome.attack_rate * 100.0);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simulation {
+    /// Number of people, all of whom start outside.
+    pub population: usize,
+    /// Number of people infected at the start.
+    pub initial_infected: usize,
+    /// Number of ticks to run for.
+    pub ticks: usize,
+    /// Number of people who try to enter each tick.
+    pub arrivals_per_tick: usize,
+    /// Number of people who leave the store each tick.
+    pub exits_per_tick: usize,
+    /// Greatest number of positions apart two people inside can be and still infect each
+    /// other. The empty slots kept for social distance count as positions.
+    pub contact_range: usize,
+    /// Chance that an infected person infects a susceptible one in range each tick. Values
+    /// outside 0 to 1 are clamped, and NaN is treated as 0.
+    pub transmission: f64,
+    /// Number of ticks an infected person stays infected before recovering.
+    pub recovery_ticks: usize,
+    /// Seed for every random choice made during a run.
+    pub seed: u64
+}
+
+impl Default for Simulation {
+    fn default() -> Simulation {
+        Simulation {
+            population: 200,
+            initial_infected: 5,
+            ticks: 100,
+            arrivals_per_tick: 10,
+            exits_per_tick: 5,
+            contact_range: 10,
+            transmission: 0.1,
+            recovery_ticks: 14,
+            seed: 0
+        }
+    }
+}
+
+impl Simulation {
+    /// Runs the simulation once for every combination of the passed in Phases and social
+    /// distances, in the order the Phases are given.
+    pub fn sweep(&self, phases: &[Phase], social_distances: &[usize]) -> Vec<Outcome> {
+        phases.iter()
+              .flat_map(|&phase| social_distances.iter().map(move |&social_distance| (phase, social_distance)))
+              .map(|(phase, social_distance)| self.run(phase, social_distance))
+              .collect()
+    }
+
+    /// Runs the simulation with an OIOO in the passed in Phase and social distance.
+    pub fn run(&self, phase: Phase, social_distance: usize) -> Outcome {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let transmission = if self.transmission.is_nan() { 0.0 } else { self.transmission.clamp(0.0, 1.0) };
+        let mut oioo = OIOOBuilder::new(phase)
+            .social_distance(social_distance)
+            .rng(StdRng::seed_from_u64(self.seed))
+            .build();
+
+        let mut health = vec![Health::Susceptible; self.population];
+        let mut infected_for = vec![0; self.population];
+        health.iter_mut().take(self.initial_infected).for_each(|h| *h = Health::Infected);
+        let mut outside = (0..self.population).collect::<Vec<_>>();
+
+        for _ in 0..self.ticks {
+            for _ in 0..self.arrivals_per_tick.min(outside.len()) {
+                let person = outside.swap_remove(rng.gen_range(0, outside.len()));
+                if let Some(turned_away) = oioo.one_in(person) {
+                    outside.push(turned_away);
+                }
+            }
+
+            let inside = oioo.seated().map(|(position, person)| (position, *person)).collect::<Vec<(Position, usize)>>();
+            let mut newly_infected = Vec::<usize>::new();
+            for &(position, person) in inside.iter().filter(|(_, p)| health[*p] == Health::Infected) {
+                for &(other_position, other) in &inside {
+                    if other != person && health[other] == Health::Susceptible
+                        && Metric::Manhattan.reaches(position, other_position, self.contact_range)
+                        && rng.gen_bool(transmission) {
+                        newly_infected.push(other);
+                    }
+                }
+            }
+
+            for person in 0..self.population {
+                if health[person] == Health::Infected {
+                    infected_for[person] += 1;
+                    if infected_for[person] >= self.recovery_ticks {
+                        health[person] = Health::Recovered;
+                    }
+                }
+            }
+            newly_infected.into_iter().for_each(|person| health[person] = Health::Infected);
+
+            outside.extend(oioo.one_out_many(self.exits_per_tick));
+        }
+
+        let initial_infected = self.initial_infected.min(self.population);
+        let infections = health.iter().filter(|h| **h != Health::Susceptible).count() - initial_infected;
+        let susceptible = self.population - initial_infected;
+
+        Outcome {
+            phase,
+            social_distance,
+            infections,
+            attack_rate: if susceptible == 0 { 0.0 } else { infections as f64 / susceptible as f64 }
+        }
+    }
+}
@@ -1,10 +1,27 @@
+#![allow(clippy::redundant_field_names, clippy::unnecessary_cast, clippy::needless_borrow)]
+
 use super::*;
+use rand::rngs::StdRng;
+use std::time::Duration;
+
+/// Slots taken by the items inside the store, lowest first.
+fn get_occupied_slots<T, R: Rng>(oioo: &OIOO<T, R>) -> Vec<usize> {
+    let mut slots = oioo.seated()
+                        .map(|(position, _)| position.column)
+                        .collect::<Vec<_>>();
+    slots.sort_unstable();
+    slots
+}
+
+/// Slots taken by <b>count</b> items seated one after the other with the passed in padding.
+fn get_padded_slots(count: usize, social_distance: usize) -> Vec<usize> {
+    (0..count).map(|k| k * (social_distance + 1)).collect()
+}
 
-fn get_number_in_store<T>(store: &Vec::<Option<T>>) -> usize {
-    store.iter()
-         .filter(|x| x.is_some())
-         .collect::<Vec<_>>()
-         .len()
+fn get_items_in_queue<T: Copy>(queue: &VecDeque<Entry<T>>) -> Vec<T> {
+    queue.iter()
+         .map(|x| x.item)
+         .collect()
 }
 
 #[test]
@@ -28,7 +45,7 @@ fn test_one_in() {
     let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 4, is_essential: true });
     assert!(oioo.store.len() == 0);
     oioo.one_in(3);
-    assert_eq!(oioo.store.len(), SOCIAL_DISTANCE + 1);
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(1, SOCIAL_DISTANCE));
 }
 
 #[test]
@@ -36,7 +53,7 @@ fn test_one_in_other_type() {
     let mut oioo = OIOO::<&str>::new(Phase::One { occupancy: 4, is_essential: true });
     assert!(oioo.store.len() == 0);
     oioo.one_in(&"test");
-    assert_eq!(oioo.store.len(), SOCIAL_DISTANCE + 1);
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(1, SOCIAL_DISTANCE));
 }
 
 #[test]
@@ -44,7 +61,7 @@ fn test_one_in_is_essential() {
     let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 4, is_essential: true });
     assert!(oioo.store.len() == 0);
     oioo.one_in(3);
-    assert_eq!(get_number_in_store(&oioo.store), 1);
+    assert_eq!(oioo.store_len(), 1);
 }
 
 #[test]
@@ -52,7 +69,7 @@ fn test_one_in_is_not_essential() {
     let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 4, is_essential: false });
     assert!(oioo.store.len() == 0);
     oioo.one_in(3);
-    assert_eq!(get_number_in_store(&oioo.store), 0);
+    assert_eq!(oioo.store_len(), 0);
 }
 
 #[test]
@@ -65,7 +82,7 @@ fn test_one_in_max_capacity_is_less_phase_one() {
         oioo.one_in(i);
     }
 
-    assert_eq!(get_number_in_store(&oioo.store), occupancy / 4);
+    assert_eq!(oioo.store_len(), occupancy / 4);
 }
 
 #[test]
@@ -78,7 +95,7 @@ fn test_one_in_max_capacity_is_less_phase_two() {
         oioo.one_in(i);
     }
 
-    assert_eq!(get_number_in_store(&oioo.store), occupancy / 2);
+    assert_eq!(oioo.store_len(), occupancy / 2);
 }
 
 #[test]
@@ -89,11 +106,11 @@ fn test_one_in_store_in_queue() {
     for x in 0..count {
         oioo.one_in(x);
     }
-    assert_eq!(oioo.store.len(), (SOCIAL_DISTANCE + 1) * count);
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(count, SOCIAL_DISTANCE));
     assert_eq!(oioo.queue.len(), 0);
 
     oioo.one_in(count + 1);
-    assert_eq!(oioo.store.len(), (SOCIAL_DISTANCE + 1) * count);
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(count, SOCIAL_DISTANCE));
     assert_eq!(oioo.queue.len(), 1);
 }
 
@@ -103,7 +120,7 @@ fn test_one_out() {
     let value = 3;
     assert!(oioo.store.len() == 0);
     oioo.one_in(value);
-    assert_eq!(oioo.store.len(), SOCIAL_DISTANCE + 1);
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(1, SOCIAL_DISTANCE));
 
     let first_result = oioo.one_out().unwrap();
     assert_eq!(first_result, value);
@@ -122,12 +139,12 @@ fn test_one_out_inserts_into_store() {
     }
 
     assert_eq!(1, oioo.queue.len());
-    assert_eq!(10, get_number_in_store(&oioo.store)); 
+    assert_eq!(10, oioo.store_len()); 
                    
     oioo.one_out();
                    
     assert_eq!(0, oioo.queue.len());
-    assert_eq!(10, get_number_in_store(&oioo.store)); 
+    assert_eq!(10, oioo.store_len()); 
 }
 
 #[test]
@@ -153,3 +170,1409 @@ fn test_one_out_is_random() {
     }
 }
 
+#[test]
+fn test_one_out_with_same_seed_is_repeatable() {
+    let mut oioo_1 = OIOO::<usize, StdRng>::with_seed(Phase::Two { occupancy: 20 }, 7);
+    let mut oioo_2 = OIOO::<usize, StdRng>::with_seed(Phase::Two { occupancy: 20 }, 7);
+
+    let count:usize = 11;
+    for x in 0..count {
+        oioo_1.one_in(x);
+        oioo_2.one_in(x);
+    }
+
+    for _ in 0..count {
+        assert_eq!(oioo_1.one_out(), oioo_2.one_out());
+    }
+}
+
+#[test]
+fn test_one_out_with_rng() {
+    let mut oioo_1 = OIOO::<usize, StdRng>::with_rng(Phase::Two { occupancy: 20 }, StdRng::seed_from_u64(3));
+    let mut oioo_2 = OIOO::<usize, StdRng>::with_seed(Phase::Two { occupancy: 20 }, 3);
+
+    for x in 0..10 {
+        oioo_1.one_in(x);
+        oioo_2.one_in(x);
+    }
+
+    for _ in 0..10 {
+        assert_eq!(oioo_1.one_out(), oioo_2.one_out());
+    }
+}
+
+#[test]
+fn test_reseed() {
+    let mut oioo = OIOO::<usize, StdRng>::with_seed(Phase::Two { occupancy: 20 }, 1);
+    for x in 0..10 {
+        oioo.one_in(x);
+    }
+
+    oioo.reseed(5);
+    let first_run = (0..10).map(|_| oioo.one_out()).collect::<Vec<_>>();
+
+    for x in 0..10 {
+        oioo.one_in(x);
+    }
+
+    oioo.reseed(5);
+    let second_run = (0..10).map(|_| oioo.one_out()).collect::<Vec<_>>();
+
+    assert_eq!(first_run, second_run);
+}
+
+#[test]
+fn test_set_phase_grows_capacity() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: true });
+    for x in 0..4 {
+        oioo.one_in(x);
+    }
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(oioo.queue.len(), 2);
+
+    oioo.set_phase(Phase::Two { occupancy: 8 });
+    assert_eq!(oioo.store_len(), 4);
+    assert_eq!(oioo.queue.len(), 0);
+    assert_eq!(oioo.phase(), Some(Phase::Two { occupancy: 8 }));
+}
+
+#[test]
+fn test_set_phase_shrinks_capacity_await_exits() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 8 });
+    for x in 0..5 {
+        oioo.one_in(x);
+    }
+
+    oioo.set_phase(Phase::One { occupancy: 8, is_essential: true });
+    assert_eq!(oioo.store_len(), 4);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![4]);
+
+    // store still holds more than the new capacity, so nobody is admitted
+    oioo.one_out();
+    oioo.one_out();
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![4]);
+
+    oioo.one_out();
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(oioo.queue.len(), 0);
+}
+
+#[test]
+fn test_set_phase_shrinks_capacity_to_queue_front() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 8 });
+    oioo.set_eviction(Eviction::ToQueueFront);
+    for x in 0..5 {
+        oioo.one_in(x);
+    }
+
+    oioo.set_phase(Phase::One { occupancy: 8, is_essential: true });
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![2, 3, 4]);
+}
+
+#[test]
+fn test_one_in_with_non_essential_is_held() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: true });
+    oioo.one_in_with(1, Essentiality::NonEssential);
+    oioo.one_in_with(2, Essentiality::Essential);
+    oioo.one_in(3);
+
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(oioo.queue.len(), 0);
+    assert_eq!(oioo.held_len(), 1);
+}
+
+#[test]
+fn test_one_in_with_essential_under_non_essential_phase() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 4, is_essential: false });
+    oioo.one_in(1);
+    oioo.one_in_with(2, Essentiality::Essential);
+
+    assert_eq!(oioo.store_len(), 1);
+    assert_eq!(oioo.held_len(), 1);
+    assert_eq!(oioo.one_out(), Some(2));
+}
+
+#[test]
+fn test_set_phase_releases_held() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: true });
+    oioo.one_in_with(1, Essentiality::NonEssential);
+    oioo.one_in_with(2, Essentiality::NonEssential);
+    assert_eq!(oioo.store_len(), 0);
+
+    oioo.set_phase(Phase::Two { occupancy: 8 });
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(oioo.held_len(), 0);
+
+    oioo.set_phase(Phase::One { occupancy: 8, is_essential: true });
+    oioo.one_in_with(3, Essentiality::Essential);
+    oioo.one_in_with(4, Essentiality::NonEssential);
+    oioo.one_in_with(5, Essentiality::Essential);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![3, 5]);
+    assert_eq!(oioo.held_len(), 1);
+}
+
+#[test]
+fn test_set_phase_eviction_respects_queue_limit() {
+    let mut oioo = OIOOBuilder::new(Phase::Reopened { occupancy: 10 })
+        .eviction(Eviction::ToQueueFront)
+        .queue_limit(1, Overflow::RejectNewcomer)
+        .build();
+    oioo.extend(0..10);
+
+    let mut turned_away = oioo.set_phase(Phase::One { occupancy: 4, is_essential: true });
+    turned_away.sort_unstable();
+    assert_eq!(oioo.store_len(), 1);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![1]);
+    assert_eq!(turned_away, vec![2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_set_phase_released_held_respects_queue_limit() {
+    let mut oioo = OIOOBuilder::new(Phase::One { occupancy: 8, is_essential: true })
+        .queue_limit(1, Overflow::DropOldest)
+        .build();
+    for x in 0..5 {
+        oioo.one_in_with(x, Essentiality::NonEssential);
+    }
+    assert_eq!(oioo.held_len(), 5);
+
+    assert_eq!(oioo.set_phase(Phase::Two { occupancy: 2 }), vec![1, 2, 3]);
+    assert_eq!(oioo.store_len(), 1);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![4]);
+    assert_eq!(oioo.held_len(), 0);
+}
+
+#[test]
+fn test_set_phase_holds_queued_non_essential() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 2 });
+    oioo.one_in_with(1, Essentiality::Essential);
+    oioo.one_in_with(2, Essentiality::NonEssential);
+    oioo.one_in_with(3, Essentiality::Essential);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![2, 3]);
+
+    oioo.set_phase(Phase::One { occupancy: 4, is_essential: true });
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![3]);
+    assert_eq!(oioo.held_len(), 1);
+}
+
+#[test]
+fn test_builder_defaults_match_new() {
+    let mut oioo = OIOOBuilder::<usize>::new(Phase::Two { occupancy: 8 }).build();
+    for x in 0..5 {
+        oioo.one_in(x);
+    }
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(4, SOCIAL_DISTANCE));
+    assert_eq!(oioo.queue.len(), 1);
+}
+
+#[test]
+fn test_builder_social_distance() {
+    let social_distance = 2;
+    let mut oioo = OIOOBuilder::<usize>::new(Phase::Two { occupancy: 8 })
+        .social_distance(social_distance)
+        .build();
+    for x in 0..5 {
+        oioo.one_in(x);
+    }
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(4, social_distance));
+    assert_eq!(oioo.store_len(), 4);
+
+    for _ in 0..5 {
+        assert!(oioo.one_out().is_some());
+    }
+    assert_eq!(oioo.one_out(), None);
+}
+
+#[test]
+fn test_builder_capacity_and_rng() {
+    let mut oioo = OIOOBuilder::<usize>::new(Phase::One { occupancy: 8, is_essential: true })
+        .capacity(3)
+        .rng(StdRng::seed_from_u64(9))
+        .build();
+    for x in 0..5 {
+        oioo.one_in(x);
+    }
+    assert_eq!(oioo.store_len(), 3);
+
+    oioo.set_phase(Phase::One { occupancy: 8, is_essential: true });
+    oioo.one_out();
+    assert_eq!(oioo.store_len(), 2);
+}
+
+#[test]
+fn test_builder_eviction() {
+    let mut oioo = OIOOBuilder::<usize>::new(Phase::Two { occupancy: 8 })
+        .eviction(Eviction::ToQueueFront)
+        .social_distance(0)
+        .build();
+    for x in 0..4 {
+        oioo.one_in(x);
+    }
+
+    oioo.set_phase(Phase::One { occupancy: 8, is_essential: true });
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![2, 3]);
+}
+
+#[test]
+fn test_one_out_large_store() {
+    let count:usize = 50_000;
+    let mut oioo = OIOO::<usize, StdRng>::with_seed(Phase::Two { occupancy: count * 2 }, 11);
+    for x in 0..count {
+        oioo.one_in(x);
+    }
+    assert_eq!(get_occupied_slots(&oioo), get_padded_slots(count, SOCIAL_DISTANCE));
+
+    let mut out = (0..count).map(|_| oioo.one_out().unwrap()).collect::<Vec<_>>();
+    assert_eq!(oioo.one_out(), None);
+
+    out.sort_unstable();
+    assert_eq!(out, (0..count).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_queue_is_first_in_first_out() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 2 });
+    for x in 0..4 {
+        oioo.one_in(x);
+    }
+    assert_eq!(oioo.queue_len(), 3);
+    assert_eq!(oioo.peek_next_in_line(), Some(&1));
+    assert_eq!(oioo.waiting().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    assert_eq!(oioo.one_out(), Some(0));
+    assert_eq!(oioo.queue_len(), 2);
+    assert_eq!(oioo.peek_next_in_line(), Some(&2));
+
+    assert_eq!(oioo.one_out(), Some(1));
+    assert_eq!(oioo.one_out(), Some(2));
+    assert_eq!(oioo.one_out(), Some(3));
+    assert_eq!(oioo.peek_next_in_line(), None);
+    assert_eq!(oioo.waiting().count(), 0);
+}
+
+#[test]
+fn test_extend() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 8 });
+    oioo.extend(0..6);
+    assert_eq!(oioo.store_len(), 4);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![4, 5]);
+}
+
+#[test]
+fn test_build_from() {
+    let oioo = OIOOBuilder::new(Phase::One { occupancy: 8, is_essential: true }).build_from(0..6);
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![2, 3, 4, 5]);
+}
+
+#[test]
+fn test_from_iter() {
+    let oioo = (0..6).collect::<OIOO<usize>>();
+    assert_eq!(oioo.store_len(), 6);
+    assert!(oioo.queue.is_empty());
+    assert_eq!(oioo.phase(), Some(Phase::Reopened { occupancy: 6 }));
+}
+
+#[test]
+fn test_into_iter_returns_store_then_queue() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: true });
+    oioo.extend(0..5);
+    oioo.one_in_with(5, Essentiality::NonEssential);
+
+    let items = oioo.into_iter().collect::<Vec<_>>();
+    let mut inside = items[..2].to_vec();
+    inside.sort_unstable();
+    assert_eq!(inside, vec![0, 1]);
+    assert_eq!(&items[2..], &[2, 3, 4, 5]);
+}
+
+#[test]
+fn test_drain() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 4 });
+    oioo.extend(0..4);
+
+    {
+        let mut drain = oioo.drain();
+        assert_eq!(drain.len(), 4);
+        drain.next();
+    }
+
+    assert!(oioo.store.is_empty());
+    assert!(oioo.queue.is_empty());
+    assert_eq!(oioo.one_out(), None);
+
+    oioo.extend(0..2);
+    assert_eq!(oioo.store_len(), 2);
+}
+
+#[test]
+fn test_inspection() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: true });
+    assert!(oioo.is_empty());
+    assert!(!oioo.is_full());
+    assert_eq!(oioo.capacity(), 2);
+    assert_eq!(oioo.phase(), Some(Phase::One { occupancy: 8, is_essential: true }));
+
+    oioo.extend(0..3);
+    oioo.one_in_with(3, Essentiality::NonEssential);
+    assert_eq!(oioo.len(), 4);
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(oioo.queue_len(), 1);
+    assert_eq!(oioo.held_len(), 1);
+    assert!(oioo.is_full());
+    assert!(!oioo.is_empty());
+
+    let mut inside = oioo.inside().copied().collect::<Vec<_>>();
+    inside.sort_unstable();
+    assert_eq!(inside, vec![0, 1]);
+    assert_eq!(oioo.waiting().copied().collect::<Vec<_>>(), vec![2]);
+    assert_eq!(oioo.held().copied().collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn test_try_one_in() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: true });
+    assert!(matches!(oioo.try_one_in(0), Ok(Admission::Admitted { .. })));
+    assert!(matches!(oioo.try_one_in(1), Ok(Admission::Admitted { .. })));
+    assert!(matches!(oioo.try_one_in(2), Ok(Admission::Queued { position: 0, dropped: None, .. })));
+    assert!(matches!(oioo.try_one_in(3), Ok(Admission::Queued { position: 1, dropped: None, .. })));
+    assert_eq!(oioo.store_len(), 2);
+    assert_eq!(oioo.queue_len(), 2);
+}
+
+#[test]
+fn test_try_one_in_rejects_non_essential() {
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: true });
+    let rejected = oioo.try_one_in_with(7, Essentiality::NonEssential).unwrap_err();
+    assert_eq!(rejected, Rejected::NonEssential(7));
+    assert_eq!(rejected.into_inner(), 7);
+    assert_eq!(oioo.held_len(), 0);
+    assert!(oioo.is_empty());
+
+    let mut oioo = OIOO::<usize>::new(Phase::One { occupancy: 8, is_essential: false });
+    assert_eq!(oioo.try_one_in(7), Err(Rejected::NonEssential(7)));
+    assert!(matches!(oioo.try_one_in_with(8, Essentiality::Essential), Ok(Admission::Admitted { .. })));
+}
+
+#[test]
+fn test_queue_limit_reject_newcomer() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .queue_limit(2, Overflow::RejectNewcomer)
+        .build();
+    assert_eq!(oioo.one_in(0), None);
+    assert_eq!(oioo.one_in(1), None);
+    assert_eq!(oioo.one_in(2), None);
+    assert_eq!(oioo.one_in(3), Some(3));
+    assert_eq!(oioo.try_one_in(4), Err(Rejected::QueueFull(4)));
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![1, 2]);
+}
+
+#[test]
+fn test_queue_limit_drop_oldest() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .queue_limit(2, Overflow::DropOldest)
+        .build();
+    oioo.extend(0..3);
+    assert_eq!(oioo.one_in(3), Some(1));
+    assert!(matches!(oioo.try_one_in(4), Ok(Admission::Queued { position: 1, dropped: Some(2), .. })));
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![3, 4]);
+}
+
+#[test]
+fn test_queue_limit_drop_random() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .queue_limit(3, Overflow::DropRandom)
+        .rng(StdRng::seed_from_u64(4))
+        .build();
+    oioo.extend(0..4);
+
+    let dropped = oioo.one_in(4).unwrap();
+    assert!((1..4).contains(&dropped));
+    assert_eq!(oioo.queue_len(), 3);
+    assert_eq!(oioo.waiting().last(), Some(&4));
+    assert!(!oioo.waiting().any(|x| *x == dropped));
+}
+
+#[test]
+fn test_queue_limit_zero() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .queue_limit(0, Overflow::DropOldest)
+        .build();
+    assert_eq!(oioo.one_in(0), None);
+    assert_eq!(oioo.one_in(1), Some(1));
+    assert_eq!(oioo.try_one_in(2), Err(Rejected::QueueFull(2)));
+}
+
+#[test]
+fn test_discipline_lifo() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .discipline(Lifo)
+        .build();
+    oioo.extend(0..4);
+    assert_eq!(oioo.one_out(), Some(0));
+    assert_eq!(oioo.one_out(), Some(3));
+    assert_eq!(oioo.one_out(), Some(2));
+    assert_eq!(oioo.one_out(), Some(1));
+}
+
+#[test]
+fn test_discipline_lottery() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .discipline(Lottery)
+        .rng(StdRng::seed_from_u64(2))
+        .build();
+    oioo.extend(0..20);
+
+    let mut admitted = (0..19).map(|_| {
+        oioo.one_out();
+        *oioo.inside().next().unwrap()
+    }).collect::<Vec<_>>();
+    assert_ne!(admitted, (1..20).collect::<Vec<_>>());
+
+    admitted.sort_unstable();
+    assert_eq!(admitted, (1..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_discipline_priority_class() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .discipline(PriorityClass::new(|x: &usize| [3, 6].contains(x)))
+        .build();
+    oioo.extend(vec![1, 2, 4, 3, 5, 6]);
+    assert_eq!(oioo.one_out(), Some(1));
+    assert_eq!(oioo.one_out(), Some(3));
+    assert_eq!(oioo.one_out(), Some(6));
+    assert_eq!(oioo.one_out(), Some(2));
+    assert_eq!(oioo.one_out(), Some(4));
+    assert_eq!(oioo.one_out(), Some(5));
+}
+
+/// Discipline that always selects an index past the end of the line.
+struct PastTheEnd;
+
+impl QueueDiscipline<usize> for PastTheEnd {
+    fn select(&mut self, line: &Line<'_, usize>, _rng: &mut dyn rand::RngCore) -> usize {
+        line.len()
+    }
+}
+
+#[test]
+#[should_panic(expected = "QueueDiscipline selected index 2 but only 2 items are waiting")]
+fn test_discipline_out_of_range_panics() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 2 })
+        .discipline(PastTheEnd)
+        .build();
+    oioo.extend(0..3);
+    oioo.one_out();
+}
+
+#[test]
+fn test_exit_strategy_first_in() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 12 })
+        .exit_strategy(FirstIn)
+        .build();
+    oioo.extend(0..5);
+    assert_eq!(oioo.one_out_many(5), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_exit_strategy_last_in() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 12 })
+        .exit_strategy(LastIn)
+        .build();
+    oioo.extend(0..5);
+    assert_eq!(oioo.one_out(), Some(4));
+    assert_eq!(oioo.one_out(), Some(3));
+    oioo.one_in(5);
+    assert_eq!(oioo.one_out_many(4), vec![5, 2, 1, 0]);
+}
+
+#[test]
+fn test_exit_strategy_longest_stay_admits_from_queue() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 4 })
+        .exit_strategy(LongestStay::default())
+        .build();
+    oioo.extend(0..4);
+    assert_eq!(oioo.one_out_many(3), vec![0, 1, 2]);
+    assert_eq!(oioo.one_out_many(3), vec![3]);
+}
+
+#[test]
+fn test_exit_strategy_weighted_random() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 10 })
+        .exit_strategy(WeightedRandom::new(|x: &usize| if *x < 3 { 0.0 } else { 1.0 }))
+        .rng(StdRng::seed_from_u64(8))
+        .build();
+    oioo.extend(0..5);
+
+    let mut first_out = oioo.one_out_many(2);
+    first_out.sort_unstable();
+    assert_eq!(first_out, vec![3, 4]);
+    assert_eq!(oioo.store_len(), 3);
+}
+
+#[test]
+fn test_exit_strategy_weighted_random_non_finite() {
+    let weights = |x: &usize| match *x {
+        1 => f64::INFINITY,
+        2 => f64::NAN,
+        _ => f64::MAX
+    };
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 10 })
+        .exit_strategy(WeightedRandom::new(weights))
+        .rng(StdRng::seed_from_u64(12))
+        .build();
+    oioo.extend(0..4);
+
+    assert_eq!(oioo.one_out(), Some(1));
+    let mut next_out = oioo.one_out_many(2);
+    next_out.sort_unstable();
+    assert_eq!(next_out, vec![0, 3]);
+    assert_eq!(oioo.one_out(), Some(2));
+}
+
+#[test]
+fn test_one_out_many() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 4 });
+    assert!(oioo.one_out_many(3).is_empty());
+
+    oioo.extend(0..3);
+    let mut out = oioo.one_out_many(10);
+    out.sort_unstable();
+    assert_eq!(out, vec![0, 1, 2]);
+    assert!(oioo.is_empty());
+}
+
+#[test]
+fn test_expire() {
+    let clock = ManualClock::new();
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 6 })
+        .clock(clock.clone())
+        .max_stay(Duration::from_secs(10))
+        .build();
+    oioo.extend(0..2);
+    clock.advance(Duration::from_secs(5));
+    oioo.extend(2..5);
+    assert_eq!(oioo.store_len(), 3);
+    assert_eq!(oioo.queue_len(), 2);
+
+    clock.advance(Duration::from_secs(4));
+    assert!(oioo.expire().is_empty());
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(oioo.expire(), vec![0, 1]);
+    assert_eq!(oioo.store_len(), 3);
+    assert_eq!(oioo.queue_len(), 0);
+
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(oioo.expire(), vec![2]);
+    clock.advance(Duration::from_secs(5));
+    let mut expired = oioo.expire();
+    expired.sort_unstable();
+    assert_eq!(expired, vec![3, 4]);
+    assert!(oioo.is_empty());
+}
+
+#[test]
+fn test_expire_without_max_stay() {
+    let clock = ManualClock::new();
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 6 })
+        .clock(clock.clone())
+        .build();
+    oioo.extend(0..2);
+    clock.advance(Duration::from_secs(1_000_000));
+    assert!(oioo.expire().is_empty());
+    assert_eq!(oioo.store_len(), 2);
+}
+
+#[test]
+fn test_phase_capacity() {
+    assert_eq!(Phase::One { occupancy: 8, is_essential: true }.capacity(Rounding::Floor), 2);
+    assert_eq!(Phase::Two { occupancy: 8 }.capacity(Rounding::Floor), 4);
+    assert_eq!(Phase::Three { occupancy: 8 }.capacity(Rounding::Floor), 6);
+    assert_eq!(Phase::Reopened { occupancy: 8 }.capacity(Rounding::Floor), 8);
+    assert_eq!(Phase::Closed.capacity(Rounding::AtLeastOne), 0);
+    assert_eq!(Phase::Custom { occupancy: 8, ratio: 0.4 }.capacity(Rounding::Floor), 3);
+}
+
+#[test]
+fn test_phase_capacity_rounding() {
+    let phase = Phase::Three { occupancy: 3 };
+    assert_eq!(phase.capacity(Rounding::Floor), 2);
+    assert_eq!(phase.capacity(Rounding::Ceil), 3);
+    assert_eq!(phase.capacity(Rounding::Nearest), 2);
+    assert_eq!(phase.capacity(Rounding::AtLeastOne), 2);
+
+    let phase = Phase::One { occupancy: 2, is_essential: true };
+    assert_eq!(phase.capacity(Rounding::Floor), 0);
+    assert_eq!(phase.capacity(Rounding::Ceil), 1);
+    assert_eq!(phase.capacity(Rounding::Nearest), 1);
+    assert_eq!(phase.capacity(Rounding::AtLeastOne), 1);
+
+    let phase = Phase::Custom { occupancy: 0, ratio: 0.5 };
+    assert_eq!(phase.capacity(Rounding::AtLeastOne), 0);
+}
+
+#[test]
+fn test_custom_capacity_is_not_off_by_float_error() {
+    assert_eq!(Phase::Custom { occupancy: 100, ratio: 0.29 }.capacity(Rounding::Floor), 29);
+    assert_eq!(Phase::Custom { occupancy: 100, ratio: 0.57 }.capacity(Rounding::Floor), 57);
+    assert_eq!(Phase::Custom { occupancy: 100, ratio: 0.07 }.capacity(Rounding::Ceil), 7);
+    assert_eq!(Phase::Custom { occupancy: 10, ratio: 0.35 }.capacity(Rounding::Floor), 3);
+    assert_eq!(Phase::Custom { occupancy: 10, ratio: 0.35 }.capacity(Rounding::Ceil), 4);
+}
+
+#[test]
+fn test_fixed_capacity_is_exact() {
+    let occupancy = (1usize << 53) + 1;
+    assert_eq!(Phase::Reopened { occupancy }.capacity(Rounding::Floor), occupancy);
+    assert_eq!(Phase::Three { occupancy: usize::MAX }.capacity(Rounding::Floor), usize::MAX / 4 * 3 + 2);
+    assert_eq!(Phase::Two { occupancy: usize::MAX }.capacity(Rounding::Ceil), usize::MAX / 2 + 1);
+}
+
+#[test]
+fn test_builder_rounding() {
+    let mut oioo = OIOOBuilder::new(Phase::One { occupancy: 3, is_essential: true })
+        .rounding(Rounding::AtLeastOne)
+        .build();
+    assert!(matches!(oioo.try_one_in(0), Ok(Admission::Admitted { .. })));
+
+    oioo.set_phase(Phase::Custom { occupancy: 5, ratio: 0.5 });
+    assert_eq!(oioo.capacity(), 2);
+}
+
+#[test]
+fn test_closed_phase() {
+    let mut oioo = OIOO::<usize>::new(Phase::Reopened { occupancy: 4 });
+    oioo.extend(0..4);
+    assert_eq!(oioo.store_len(), 4);
+
+    oioo.set_phase(Phase::Closed);
+    assert_eq!(oioo.capacity(), 0);
+    assert_eq!(oioo.try_one_in(4), Err(Rejected::Closed(4)));
+    assert_eq!(oioo.one_in(5), None);
+    assert_eq!(oioo.held_len(), 1);
+
+    oioo.one_out_many(4);
+    oioo.set_phase(Phase::Three { occupancy: 4 });
+    assert_eq!(oioo.inside().collect::<Vec<_>>(), vec![&5]);
+}
+
+struct EvenOnly {
+    capacity: usize
+}
+
+impl CapacityPolicy<usize> for EvenOnly {
+    fn allowed_capacity(&self, _venue: &Venue) -> usize {
+        self.capacity
+    }
+
+    fn may_enter(&self, item: &usize, _essentiality: Option<Essentiality>, _venue: &Venue) -> bool {
+        item % 2 == 0
+    }
+}
+
+struct GrowsWithLine;
+
+impl CapacityPolicy<usize> for GrowsWithLine {
+    fn allowed_capacity(&self, venue: &Venue) -> usize {
+        1 + venue.waiting
+    }
+}
+
+#[test]
+fn test_capacity_policy() {
+    let mut oioo = OIOOBuilder::with_policy(EvenOnly { capacity: 2 }).build();
+    oioo.extend(0..7);
+    assert_eq!(oioo.phase(), None);
+    assert_eq!(oioo.capacity(), 2);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![4, 6]);
+    assert_eq!(oioo.held().copied().collect::<Vec<_>>(), vec![1, 3, 5]);
+    assert_eq!(oioo.try_one_in(7), Err(Rejected::Denied(7)));
+
+    oioo.set_phase(Phase::Reopened { occupancy: 8 });
+    assert_eq!(oioo.store_len(), 7);
+
+    oioo.set_policy(EvenOnly { capacity: 8 });
+    assert_eq!(oioo.held().copied().collect::<Vec<_>>(), Vec::<usize>::new());
+    assert_eq!(oioo.store_len(), 7);
+}
+
+#[test]
+fn test_capacity_policy_uses_venue_state() {
+    let mut oioo = OIOOBuilder::with_policy(GrowsWithLine).build();
+    assert!(matches!(oioo.try_one_in(0), Ok(Admission::Admitted { .. })));
+    assert!(matches!(oioo.try_one_in(1), Ok(Admission::Queued { position: 0, dropped: None, .. })));
+    assert_eq!(oioo.capacity(), 2);
+    assert!(matches!(oioo.try_one_in(2), Ok(Admission::Admitted { .. })));
+}
+
+#[test]
+fn test_line_positions() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 8 });
+    oioo.extend(0..3);
+    let mut columns = oioo.seated().map(|(p, _)| p.column).collect::<Vec<_>>();
+    columns.sort_unstable();
+    assert_eq!(columns, vec![0, SOCIAL_DISTANCE + 1, (SOCIAL_DISTANCE + 1) * 2]);
+
+    // the others keep their seats and the next item fills the hole
+    let out = oioo.one_out().unwrap();
+    let mut columns = oioo.seated().map(|(p, _)| p.column).collect::<Vec<_>>();
+    columns.sort_unstable();
+    let mut expected = vec![0, SOCIAL_DISTANCE + 1, (SOCIAL_DISTANCE + 1) * 2];
+    expected.remove(out);
+    assert_eq!(columns, expected);
+
+    oioo.one_in(3);
+    assert!(oioo.seated().any(|(p, item)| *item == 3 && p.column == out * (SOCIAL_DISTANCE + 1)));
+}
+
+#[test]
+fn test_floor_plan_manhattan() {
+    let mut oioo = OIOOBuilder::new(Phase::Reopened { occupancy: 9 })
+        .floor_plan(FloorPlan::grid(3, 3, 2, Metric::Manhattan))
+        .build();
+    oioo.extend(0..6);
+    assert_eq!(oioo.store_len(), 5);
+    assert_eq!(oioo.queue_len(), 1);
+    assert!(oioo.is_full());
+
+    let mut positions = oioo.seated().map(|(p, _)| p).collect::<Vec<_>>();
+    positions.sort_unstable();
+    assert_eq!(positions, vec![Position::new(0, 0), Position::new(0, 2), Position::new(1, 1),
+                               Position::new(2, 0), Position::new(2, 2)]);
+}
+
+#[test]
+fn test_floor_plan_keeps_distance_after_exits() {
+    let plan = FloorPlan::grid(6, 8, 3, Metric::Euclidean);
+    let mut oioo = OIOOBuilder::new(Phase::Reopened { occupancy: 48 })
+        .floor_plan(plan)
+        .rng(StdRng::seed_from_u64(6))
+        .build();
+    oioo.extend(0..40);
+    assert!(oioo.queue_len() > 0);
+
+    for _ in 0..30 {
+        oioo.one_out();
+        let positions = oioo.seated().map(|(p, _)| p).collect::<Vec<_>>();
+        for (i, a) in positions.iter().enumerate() {
+            for b in &positions[i + 1..] {
+                assert!(Metric::Euclidean.distance(*a, *b) >= 3.0);
+            }
+        }
+        assert!(positions.iter().all(|p| oioo.floor_plan().unwrap().is_occupied(*p)));
+    }
+}
+
+#[test]
+fn test_floor_plan_respects_capacity() {
+    let mut oioo = OIOOBuilder::new(Phase::One { occupancy: 8, is_essential: true })
+        .floor_plan(FloorPlan::grid(4, 4, 1, Metric::Manhattan))
+        .build();
+    oioo.extend(0..4);
+    assert_eq!(oioo.store_len(), 2);
+
+    let mut drained = oioo.drain().collect::<Vec<_>>();
+    drained.sort_unstable();
+    assert_eq!(drained, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_floor_plan_drain_frees_cells() {
+    let mut oioo = OIOOBuilder::new(Phase::Reopened { occupancy: 4 })
+        .floor_plan(FloorPlan::grid(1, 4, 2, Metric::Manhattan))
+        .build();
+    oioo.extend(0..2);
+    assert!(oioo.is_full());
+
+    oioo.drain();
+    oioo.extend(0..2);
+    assert_eq!(oioo.store_len(), 2);
+}
+
+#[test]
+fn test_layout_parse() {
+    let layout = Layout::parse("\n#D#\n.S.\n###\n\n").unwrap();
+    assert_eq!(layout.rows(), 3);
+    assert_eq!(layout.columns(), 3);
+    assert_eq!(layout.cell(Position::new(0, 1)), Some(Cell::Door));
+    assert_eq!(layout.cell(Position::new(1, 1)), Some(Cell::Seat));
+    assert_eq!(layout.cell(Position::new(1, 2)), Some(Cell::Floor));
+    assert_eq!(layout.cell(Position::new(3, 0)), None);
+}
+
+#[test]
+fn test_layout_parse_errors() {
+    match Layout::parse("###\n#x#\n###") {
+        Err(LayoutError::UnknownCell { line, column, found }) => assert_eq!((line, column, found), (2, 2, 'x')),
+        other => panic!("unexpected {:?}", other)
+    }
+
+    match Layout::parse("\n###\n##\n###") {
+        Err(LayoutError::RaggedRow { line, column, width, expected }) => assert_eq!((line, column, width, expected), (3, 3, 2, 3)),
+        other => panic!("unexpected {:?}", other)
+    }
+
+    assert!(matches!(Layout::parse(" \n\n"), Err(LayoutError::Empty)));
+    assert!(matches!(Layout::from_file("does/not/exist.txt"), Err(LayoutError::Io(_))));
+    assert_eq!(Layout::parse("#?").unwrap_err().to_string(), "line 1, column 2: unknown cell '?'");
+    assert_eq!(Layout::parse("##\n###").unwrap_err().to_string(), "line 2, column 3: row is 3 cells wide, expected 2");
+}
+
+#[test]
+fn test_layout_floor_plan() {
+    let layout = Layout::parse("#####\n#...#\n#...#\n##D##").unwrap();
+    let floor_plan = layout.floor_plan(2, Metric::Manhattan);
+    assert!(!floor_plan.is_usable(Position::new(0, 0)));
+    assert!(!floor_plan.is_usable(Position::new(3, 2)));
+    assert!(floor_plan.is_usable(Position::new(1, 1)));
+    assert_eq!(floor_plan.capacity(), 3);
+
+    let mut oioo = OIOOBuilder::from_floor_plan(floor_plan).build();
+    oioo.extend(0..5);
+    assert_eq!(oioo.capacity(), 3);
+    assert_eq!(oioo.store_len(), 3);
+    assert!(oioo.seated().all(|(p, _)| layout.cell(p) == Some(Cell::Floor)));
+}
+
+#[test]
+fn test_floor_plan_capacity_large_room() {
+    let mut floor_plan = FloorPlan::grid(30, 30, 3, Metric::Manhattan);
+    let mut seated = 0;
+    while let Some(position) = floor_plan.find_seat() {
+        floor_plan.take(position);
+        seated += 1;
+    }
+    assert_eq!(FloorPlan::grid(30, 30, 3, Metric::Manhattan).capacity(), seated);
+
+    let floor_plan = FloorPlan::grid(300, 300, 3, Metric::Manhattan);
+    assert_eq!(floor_plan.capacity(), 15_075);
+    assert!(floor_plan.positions().all(|p| !floor_plan.is_occupied(p)));
+}
+
+#[test]
+fn test_floor_plan_large_room() {
+    let mut oioo = OIOOBuilder::from_floor_plan(FloorPlan::grid(300, 300, 3, Metric::Manhattan))
+        .rng(StdRng::seed_from_u64(16))
+        .build();
+    oioo.extend(0..20_000);
+    assert_eq!(oioo.store_len(), 15_075);
+    assert_eq!(oioo.queue_len(), 4_925);
+    assert!(oioo.is_full());
+
+    for _ in 0..4_925 {
+        oioo.one_out();
+        assert_eq!(oioo.store_len(), 15_075);
+    }
+    assert_eq!(oioo.queue_len(), 0);
+
+    let floor_plan = oioo.floor_plan().unwrap();
+    assert!(oioo.seated().all(|(p, _)| floor_plan.is_occupied(p)));
+    assert!(floor_plan.positions().all(|p| !floor_plan.is_available(p)));
+}
+
+#[test]
+fn test_layout_seats_only() {
+    let layout = Layout::parse("S.S\n...\nS.S").unwrap();
+    let floor_plan = layout.floor_plan(1, Metric::Euclidean);
+    assert_eq!(floor_plan.capacity(), 4);
+    assert!(!floor_plan.is_usable(Position::new(1, 1)));
+}
+
+#[test]
+fn test_pack_beats_first_fit() {
+    let floor_plan = FloorPlan::grid(4, 5, 3, Metric::Manhattan);
+    let packing = floor_plan.pack();
+    assert!(packing.is_exact());
+    assert_eq!(floor_plan.capacity(), 4);
+    assert_eq!(packing.occupancy(), 5);
+
+    let positions = packing.positions();
+    for (i, a) in positions.iter().enumerate() {
+        assert!(floor_plan.is_usable(*a));
+        assert!(positions[i + 1..].iter().all(|b| Metric::Manhattan.distance(*a, *b) >= 3.0));
+    }
+}
+
+#[test]
+fn test_pack_with_limit() {
+    let floor_plan = FloorPlan::grid(4, 5, 3, Metric::Manhattan);
+    let packing = floor_plan.pack_with_limit(0);
+    assert!(!packing.is_exact());
+    assert_eq!(packing.occupancy(), floor_plan.capacity());
+}
+
+#[test]
+fn test_pack_large_room_stops_early() {
+    let floor_plan = FloorPlan::grid(8, 8, 3, Metric::Manhattan);
+    let packing = floor_plan.pack();
+    assert!(packing.is_exact());
+    assert_eq!(packing.occupancy(), 13);
+
+    let floor_plan = FloorPlan::grid(100, 100, 3, Metric::Manhattan);
+    let started = std::time::Instant::now();
+    let packing = floor_plan.pack();
+    assert!(started.elapsed() < Duration::from_secs(30));
+    assert!(!packing.is_exact());
+    assert!(packing.occupancy() >= floor_plan.capacity());
+}
+
+#[test]
+fn test_pack_respects_layout() {
+    let layout = Layout::parse("#....\n.#...\n..#..\n...#.").unwrap();
+    let floor_plan = layout.floor_plan(2, Metric::Euclidean);
+    let packing = floor_plan.pack();
+    assert!(packing.is_exact());
+    assert!(packing.positions().iter().all(|p| layout.cell(*p) == Some(Cell::Floor)));
+    assert!(packing.occupancy() >= floor_plan.capacity());
+}
+
+#[test]
+fn test_oioo_admits_into_packed_seats() {
+    let floor_plan = FloorPlan::grid(4, 5, 3, Metric::Manhattan);
+    let packing = floor_plan.pack();
+    let mut oioo = OIOOBuilder::from_floor_plan(floor_plan.seated_at(&packing))
+        .rng(StdRng::seed_from_u64(4))
+        .build();
+    oioo.extend(0..8);
+    assert_eq!(oioo.capacity(), 5);
+    assert_eq!(oioo.store_len(), 5);
+
+    for _ in 0..6 {
+        oioo.one_out();
+        oioo.one_in(100);
+        assert_eq!(oioo.store_len(), 5);
+        assert!(oioo.seated().all(|(p, _)| packing.positions().contains(&p)));
+    }
+}
+
+/// Exit strategy that removes the listed items, in order.
+struct LeaveInOrder(Vec<usize>);
+
+impl ExitStrategy<usize> for LeaveInOrder {
+    fn select(&mut self, room: &Room<'_, usize>, _rng: &mut dyn rand::RngCore) -> usize {
+        let next = self.0.remove(0);
+        room.iter().position(|item| *item == next).unwrap()
+    }
+}
+
+fn admitted_seat<T>(admission: Result<Admission<T>, Rejected<T>>) -> SeatId {
+    match admission {
+        Ok(Admission::Admitted { seat, .. }) => seat,
+        _ => panic!("item was not admitted")
+    }
+}
+
+#[test]
+fn test_seats_stay_put() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 12 })
+        .social_distance(1)
+        .rng(StdRng::seed_from_u64(19))
+        .build();
+    let seats = (0..6).map(|x| admitted_seat(oioo.try_one_in(x))).collect::<Vec<_>>();
+
+    for _ in 0..3 {
+        let out = oioo.one_out().unwrap();
+        assert_eq!(oioo.get(seats[out]), None);
+        for (item, seat) in seats.iter().enumerate().filter(|(item, _)| oioo.inside().any(|x| x == item)) {
+            assert_eq!(oioo.get(*seat), Some(&item));
+            assert_eq!(oioo.seat_position(*seat), Some(Position::new(0, item * 2)));
+        }
+    }
+    assert_eq!(oioo.seats().count(), 3);
+}
+
+#[test]
+fn test_seat_id_not_reused() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 2 });
+    let first = admitted_seat(oioo.try_one_in(0));
+    oioo.one_out();
+    let second = admitted_seat(oioo.try_one_in(1));
+    assert_ne!(first, second);
+    assert_eq!(oioo.get(first), None);
+    assert_eq!(oioo.get(second), Some(&1));
+    assert_eq!(oioo.seat_position(second), Some(Position::new(0, 0)));
+}
+
+#[test]
+fn test_line_placement() {
+    for &(placement, column) in &[(Placement::FirstFit, 1), (Placement::BestFit, 4)] {
+        let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 12 })
+            .social_distance(0)
+            .placement(placement)
+            .exit_strategy(LeaveInOrder(vec![1, 2, 4]))
+            .build();
+        oioo.extend(0..6);
+        oioo.one_out_many(3);
+
+        // holes are left at 1..=2 and 4
+        oioo.one_in(6);
+        assert!(oioo.seated().any(|(p, item)| *item == 6 && p.column == column));
+    }
+}
+
+#[test]
+fn test_floor_plan_best_fit() {
+    let first_fit = OIOOBuilder::new(Phase::Reopened { occupancy: 64 })
+        .floor_plan(FloorPlan::grid(8, 8, 3, Metric::Manhattan))
+        .build_from(0..20);
+    let best_fit = OIOOBuilder::new(Phase::Reopened { occupancy: 64 })
+        .floor_plan(FloorPlan::grid(8, 8, 3, Metric::Manhattan))
+        .placement(Placement::BestFit)
+        .build_from(0..20);
+    assert_eq!(first_fit.store_len(), 12);
+    assert_eq!(best_fit.store_len(), 13);
+
+    let positions = best_fit.seated().map(|(p, _)| p).collect::<Vec<_>>();
+    for (i, a) in positions.iter().enumerate() {
+        assert!(positions[i + 1..].iter().all(|b| Metric::Manhattan.distance(*a, *b) >= 3.0));
+    }
+}
+
+#[test]
+fn test_from_floor_plan_best_fit() {
+    let first_fit = OIOOBuilder::from_floor_plan(FloorPlan::grid(8, 8, 3, Metric::Manhattan))
+        .build_from(0..20);
+    let best_fit = OIOOBuilder::from_floor_plan(FloorPlan::grid(8, 8, 3, Metric::Manhattan))
+        .placement(Placement::BestFit)
+        .build_from(0..20);
+    assert_eq!(first_fit.capacity(), 12);
+    assert_eq!(first_fit.store_len(), 12);
+    assert_eq!(best_fit.capacity(), 13);
+    assert_eq!(best_fit.store_len(), 13);
+    assert!(best_fit.is_full());
+}
+
+#[test]
+fn test_ticket_status() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 4 })
+        .rng(StdRng::seed_from_u64(20))
+        .build();
+    let tickets = (0..4).map(|x| oioo.try_one_in(x).unwrap().ticket()).collect::<Vec<_>>();
+    assert_eq!(oioo.status(tickets[0]), Status::Inside);
+    assert_eq!(oioo.status(tickets[3]), Status::Waiting);
+    assert_eq!(oioo.position(tickets[2]), Some(0));
+    assert_eq!(oioo.position(tickets[3]), Some(1));
+    assert_eq!(oioo.position(tickets[0]), None);
+    assert!(oioo.seat_of(tickets[1]).is_some());
+    assert_eq!(oioo.seat_of(tickets[2]), None);
+
+    let out = oioo.one_out().unwrap();
+    assert_eq!(oioo.status(tickets[out]), Status::Gone);
+    assert_eq!(oioo.status(tickets[2]), Status::Inside);
+    assert_eq!(oioo.position(tickets[3]), Some(0));
+}
+
+#[test]
+fn test_leave_from_queue_and_held() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 2 });
+    let inside = oioo.try_one_in(0).unwrap().ticket();
+    let first = oioo.try_one_in(1).unwrap().ticket();
+    let second = oioo.try_one_in(2).unwrap().ticket();
+
+    assert_eq!(oioo.leave(first), Some(1));
+    assert_eq!(oioo.leave(first), None);
+    assert_eq!(oioo.position(second), Some(0));
+
+    oioo.set_phase(Phase::Closed);
+    assert_eq!(oioo.status(second), Status::Held);
+    assert_eq!(oioo.status(inside), Status::Inside);
+    assert_eq!(oioo.leave(second), Some(2));
+    assert_eq!(oioo.held_len(), 0);
+}
+
+#[test]
+fn test_leave_admits_from_queue() {
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 4 })
+        .queue_limit(1, Overflow::DropOldest)
+        .build();
+    let tickets = (0..4).map(|x| oioo.try_one_in(x).unwrap().ticket()).collect::<Vec<_>>();
+    assert_eq!(oioo.status(tickets[2]), Status::Gone); // dropped from the full queue
+
+    assert_eq!(oioo.leave(tickets[1]), Some(1));
+    assert_eq!(oioo.status(tickets[3]), Status::Inside);
+    assert_eq!(oioo.queue_len(), 0);
+    assert!(oioo.seat_of(tickets[3]).is_some());
+}
+
+#[test]
+fn test_contact_log_line() {
+    let clock = ManualClock::new();
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 8 })
+        .social_distance(1)
+        .contact_log(2)
+        .clock(clock.clone())
+        .build();
+    assert!(OIOO::<usize>::new(Phase::Two { occupancy: 8 }).contact_log().is_none());
+
+    // positions 0, 2, 4 and 6
+    let tickets = (0..4).map(|x| oioo.try_one_in(x).unwrap().ticket()).collect::<Vec<_>>();
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(oioo.leave(tickets[0]), Some(0));
+    clock.advance(Duration::from_secs(10));
+
+    let log = oioo.contact_log().unwrap();
+    assert_eq!(log.range(), 2);
+    assert_eq!(log.contacts().count(), 3);
+    let of_second = log.contacts_of(tickets[1], Duration::from_secs(0));
+    assert_eq!(of_second.len(), 2);
+    assert_eq!(of_second[0].other(tickets[1]), Some(tickets[0]));
+    assert_eq!(of_second[0].end, Some(Duration::from_secs(10)));
+    assert_eq!(of_second[1].other(tickets[1]), Some(tickets[2]));
+    assert_eq!(of_second[1].end, None);
+    assert!(log.contacts_of(tickets[3], Duration::from_secs(0)).iter().all(|c| c.other(tickets[3]) == Some(tickets[2])));
+
+    // the contact with the first item ended before 15 seconds
+    assert_eq!(log.contacts_of(tickets[1], Duration::from_secs(15)).len(), 1);
+}
+
+#[test]
+fn test_contact_graph() {
+    let clock = ManualClock::new();
+    let mut oioo = OIOOBuilder::new(Phase::Reopened { occupancy: 9 })
+        .floor_plan(FloorPlan::grid(3, 3, 2, Metric::Euclidean))
+        .contact_log(2)
+        .clock(clock.clone())
+        .build();
+    // corners, each 2 apart from two others and about 2.8 from the opposite corner
+    let tickets = (0..4).map(|x| oioo.try_one_in(x).unwrap().ticket()).collect::<Vec<_>>();
+    clock.advance(Duration::from_secs(30));
+
+    let graph = oioo.contact_graph();
+    assert_eq!(graph.len(), 4);
+    assert!(graph.iter().all(|(a, b, d)| a < b && *d == Duration::from_secs(30)));
+    assert!(!graph.iter().any(|(a, b, _)| (*a, *b) == (tickets[0], tickets[3])));
+
+    oioo.drain().for_each(drop);
+    clock.advance(Duration::from_secs(30));
+    assert!(oioo.contact_graph().iter().all(|(_, _, d)| *d == Duration::from_secs(30)));
+    assert!(oioo.contact_log().unwrap().contacts().all(|c| c.end.is_some()));
+}
+
+/// Risk model that only counts time in contact.
+struct OverlapOnly;
+
+impl RiskModel for OverlapOnly {
+    fn score(&self, exposure: &Exposure) -> f64 {
+        exposure.duration.as_secs_f64()
+    }
+}
+
+#[test]
+fn test_rank_exposed_by_overlap() {
+    let clock = ManualClock::new();
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 8 })
+        .social_distance(0)
+        .contact_log(3)
+        .clock(clock.clone())
+        .build();
+    let tickets = (0..4).map(|x| oioo.try_one_in(x).unwrap().ticket()).collect::<Vec<_>>();
+    clock.advance(Duration::from_secs(10));
+    oioo.leave(tickets[3]);
+    clock.advance(Duration::from_secs(10));
+    oioo.leave(tickets[1]);
+    clock.advance(Duration::from_secs(10));
+
+    let ranked = oioo.rank_exposed(tickets[0], &OverlapOnly);
+    assert_eq!(ranked, vec![(tickets[2], 30.0), (tickets[1], 20.0), (tickets[3], 10.0)]);
+    assert!(oioo.rank_exposed(tickets[0], &InverseSquare::new()).iter().all(|(_, score)| *score > 0.0));
+    assert!(OIOO::<usize>::new(Phase::Two { occupancy: 8 }).rank_exposed(tickets[0], &OverlapOnly).is_empty());
+}
+
+#[test]
+fn test_rank_exposed_modifiers() {
+    let clock = ManualClock::new();
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 8 })
+        .social_distance(0)
+        .contact_log(1)
+        .clock(clock.clone())
+        .build();
+    let flagged = oioo.try_one_in(0).unwrap().ticket();
+    let exposed = oioo.try_one_in(1).unwrap().ticket();
+    clock.advance(Duration::from_secs(600));
+
+    // ten minutes at a distance of one
+    assert_eq!(oioo.rank_exposed(flagged, &InverseSquare::new()), vec![(exposed, 5.0)]);
+    assert_eq!(oioo.rank_exposed(exposed, &InverseSquare { ventilation: 0.5 }), vec![(flagged, 2.5)]);
+
+    oioo.set_risk_modifier(flagged, 0.5);
+    oioo.set_risk_modifier(exposed, 0.5);
+    assert_eq!(oioo.contact_log().unwrap().modifier(flagged), 0.5);
+    assert_eq!(oioo.rank_exposed(flagged, &InverseSquare::new()), vec![(exposed, 1.25)]);
+}
+
+#[test]
+fn test_quarantine_floor_plan() {
+    let mut oioo = OIOOBuilder::new(Phase::Reopened { occupancy: 9 })
+        .floor_plan(FloorPlan::grid(3, 3, 2, Metric::Manhattan))
+        .build();
+    // (0,0), (0,2), (1,1), (2,0), (2,2) with one waiting
+    let tickets = (0..6).map(|x| oioo.try_one_in(x).unwrap().ticket()).collect::<Vec<_>>();
+
+    let isolated = oioo.quarantine(tickets[2], 2);
+    assert_eq!(isolated, vec![tickets[2], tickets[0], tickets[1], tickets[3], tickets[4]]);
+    assert_eq!(oioo.isolated_len(), 5);
+    assert_eq!(oioo.isolated().copied().collect::<Vec<_>>(), vec![2, 0, 1, 3, 4]);
+    assert_eq!(oioo.status(tickets[5]), Status::Inside);
+    assert!(oioo.floor_plan().unwrap().is_occupied(Position::new(0, 0)));
+    assert_eq!(oioo.len(), 6);
+
+    assert_eq!(oioo.leave(tickets[0]), Some(0));
+    assert_eq!(oioo.release(tickets[0]), None);
+    assert_eq!(oioo.release(tickets[3]), Some(3));
+    assert_eq!(oioo.release_all(), vec![2, 1, 4]);
+    assert_eq!(oioo.len(), 1);
+}
+
+#[test]
+fn test_quarantine_outside_store() {
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 2 });
+    let inside = oioo.try_one_in(0).unwrap().ticket();
+    let waiting = oioo.try_one_in(1).unwrap().ticket();
+
+    assert_eq!(oioo.quarantine(waiting, 100), vec![waiting]);
+    assert_eq!(oioo.status(waiting), Status::Isolated);
+    assert_eq!(oioo.status(inside), Status::Inside);
+    assert_eq!(oioo.quarantine(waiting, 100), vec![]);
+
+    assert_eq!(oioo.quarantine(inside, 0), vec![inside]);
+    assert_eq!(oioo.store_len(), 0);
+    assert_eq!(oioo.drain().collect::<Vec<_>>(), vec![1, 0]);
+    assert_eq!(oioo.isolated_len(), 0);
+}
+
+#[test]
+fn test_simulation_is_reproducible() {
+    let simulation = Simulation { transmission: 0.2, ..Simulation::default() };
+    let phase = Phase::Two { occupancy: 100 };
+    assert_eq!(simulation.run(phase, 6), simulation.run(phase, 6));
+
+    let outcome = simulation.run(phase, 6);
+    assert_eq!(outcome.phase, phase);
+    assert_eq!(outcome.social_distance, 6);
+    assert!(outcome.infections > 0);
+    assert_eq!(outcome.attack_rate, outcome.infections as f64 / 195.0);
+}
+
+#[test]
+fn test_simulation_sweep() {
+    let simulation = Simulation { transmission: 0.2, ..Simulation::default() };
+    let phases = [Phase::One { occupancy: 100, is_essential: true }, Phase::Two { occupancy: 100 }];
+    let outcomes = simulation.sweep(&phases, &[6, 10]);
+    assert_eq!(outcomes.iter().map(|o| (o.phase, o.social_distance)).collect::<Vec<_>>(),
+               vec![(phases[0], 6), (phases[0], 10), (phases[1], 6), (phases[1], 10)]);
+
+    // a larger capacity packs more people in range of each other
+    assert!(outcomes[2].attack_rate > outcomes[0].attack_rate);
+    // nobody is in range once the social distance exceeds the contact range
+    assert_eq!(outcomes[1].infections, 0);
+    assert_eq!(outcomes[3].infections, 0);
+}
+
+#[test]
+fn test_simulation_without_spread() {
+    let simulation = Simulation { transmission: 0.0, ..Simulation::default() };
+    assert_eq!(simulation.run(Phase::Reopened { occupancy: 100 }, 0).attack_rate, 0.0);
+
+    let simulation = Simulation { population: 3, initial_infected: 3, ..Simulation::default() };
+    assert_eq!(simulation.run(Phase::Reopened { occupancy: 100 }, 0).attack_rate, 0.0);
+}
+
+#[test]
+fn test_simulation_clamps_transmission() {
+    let clamped = Simulation { transmission: 2.0, ..Simulation::default() };
+    let certain = Simulation { transmission: 1.0, ..Simulation::default() };
+    assert_eq!(clamped.run(Phase::Two { occupancy: 40 }, 2), certain.run(Phase::Two { occupancy: 40 }, 2));
+
+    let simulation = Simulation { transmission: -1.0, ..Simulation::default() };
+    assert_eq!(simulation.run(Phase::Two { occupancy: 40 }, 2).infections, 0);
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Admitted(usize, Position),
+    Queued(usize, usize),
+    Exited(usize, Position),
+    Promoted(usize, Position)
+}
+
+/// Observer that records every event it is notified of.
+struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<Event>>>);
+
+impl OIOOObserver<usize> for Recorder {
+    fn admitted(&mut self, item: &usize, position: Position) {
+        self.0.lock().unwrap().push(Event::Admitted(*item, position));
+    }
+
+    fn queued(&mut self, item: &usize, position: usize) {
+        self.0.lock().unwrap().push(Event::Queued(*item, position));
+    }
+
+    fn exited(&mut self, item: &usize, position: Position) {
+        self.0.lock().unwrap().push(Event::Exited(*item, position));
+    }
+
+    fn promoted(&mut self, item: &usize, position: Position) {
+        self.0.lock().unwrap().push(Event::Promoted(*item, position));
+    }
+}
+
+#[test]
+fn test_observer_events() {
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::<Event>::new()));
+    let mut oioo = OIOOBuilder::new(Phase::Two { occupancy: 4 })
+        .social_distance(1)
+        .exit_strategy(FirstIn)
+        .observer(Recorder(events.clone()))
+        .build();
+    oioo.extend(0..4);
+    assert_eq!(oioo.one_out(), Some(0));
+
+    assert_eq!(*events.lock().unwrap(), vec![
+        Event::Admitted(0, Position::new(0, 0)),
+        Event::Admitted(1, Position::new(0, 2)),
+        Event::Queued(2, 0),
+        Event::Queued(3, 1),
+        Event::Exited(0, Position::new(0, 0)),
+        Event::Promoted(2, Position::new(0, 0))
+    ]);
+}
+
+#[test]
+fn test_observer_other_exits() {
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::<Event>::new()));
+    let mut oioo = OIOO::<usize>::new(Phase::Two { occupancy: 4 });
+    oioo.add_observer(Recorder(events.clone()));
+    let ticket = oioo.try_one_in(0).unwrap().ticket();
+    oioo.one_in(1);
+    oioo.leave(ticket);
+    drop(oioo.drain());
+
+    let exited = events.lock().unwrap().iter().filter_map(|e| match e {
+        Event::Exited(item, _) => Some(*item),
+        _ => None
+    }).collect::<Vec<_>>();
+    assert_eq!(exited, vec![0, 1]);
+}
+
+#[test]
+fn test_observer_eviction_events() {
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::<Event>::new()));
+    let mut oioo = OIOOBuilder::new(Phase::Reopened { occupancy: 4 })
+        .eviction(Eviction::ToQueueFront)
+        .observer(Recorder(events.clone()))
+        .build();
+    oioo.extend(0..5);
+    events.lock().unwrap().clear();
+
+    oioo.set_phase(Phase::Two { occupancy: 4 });
+    let queued = events.lock().unwrap().iter().filter_map(|e| match e {
+        Event::Queued(item, position) => Some((*item, *position)),
+        _ => None
+    }).collect::<Vec<_>>();
+    assert_eq!(queued, vec![(2, 0), (3, 1)]);
+    assert_eq!(get_items_in_queue(&oioo.queue), vec![2, 3, 4]);
+}
@@ -0,0 +1,26 @@
+/// Handle to an item pushed with `try_one_in`, used to follow and remove that item while it
+/// is inside the store or waiting outside.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Ticket(u64);
+
+impl Ticket {
+    pub(crate) fn new(id: u64) -> Ticket {
+        Ticket(id)
+    }
+}
+
+/// Where the item a Ticket was issued for currently is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    /// The item is inside the store.
+    Inside,
+    /// The item is waiting in the queue for space in the store.
+    Waiting,
+    /// The item is held outside because the current Phase does not allow it in.
+    Held,
+    /// The item was isolated by `quarantine` and hasn't been released.
+    Isolated,
+    /// The item has left the OIOO, whether through an exit, `leave` or being dropped from
+    /// a full queue.
+    Gone
+}